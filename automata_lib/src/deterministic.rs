@@ -1,4 +1,4 @@
-/**
+/*
  * Gyorgy Matyas
  * gmim2236
  * 1.B.01
@@ -7,13 +7,58 @@
  * pda
  */
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::fmt;
 use std::fs::File;
 use std::io::{self, BufRead};
-use std::path::Path;
 
 use crate::traits::Automaton;
+use crate::error::{AutomatonError, SourceLines};
+use crate::nondeterministic::NonDeterministicAutomaton;
+use crate::minimization::MinimizationReport;
+use crate::utils::{dot_id, escape_dot_label, split_word};
+use crate::regex::{eliminate_states, Regex};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProductOperation {
+    Intersection,
+    Union,
+    Difference,
+    SymmetricDifference,
+}
+
+impl ProductOperation {
+    fn accepts(self, left: bool, right: bool) -> bool {
+        match self {
+            ProductOperation::Intersection => left && right,
+            ProductOperation::Union => left || right,
+            ProductOperation::Difference => left && !right,
+            ProductOperation::SymmetricDifference => left != right,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AcceptingSide {
+    Left,
+    Right,
+}
+
+// shortest word accepted by exactly one of the two automata
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EquivalenceWitness {
+    pub word: Vec<String>,
+    pub accepted_by: AcceptingSide,
+}
+
+// states visited while reading a word, stuck_at is the index of the first
+// symbol without a transition (0 also when there is no start state)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeterministicRun {
+    pub states: Vec<String>,
+    pub stuck_at: Option<usize>,
+    pub accepted: bool,
+}
 
 #[derive(Clone, Debug)]
 pub struct DeterministicAutomaton {
@@ -24,6 +69,24 @@ pub struct DeterministicAutomaton {
     pub transitions: HashMap<(String, String), String>,
 }
 
+// names the product state of a pair (p,q), primes are appended when another
+// pair already has that name: the pairs ("a,b", "c") and ("a", "b,c") both read (a,b,c)
+fn intern_pair(names: &mut HashMap<(String, String), String>, states: &mut HashSet<String>, pair: &(String, String)) -> String {
+    let mut name = format!("({},{})", pair.0, pair.1);
+    while states.contains(&name) {
+        name.push('\'');
+    }
+    states.insert(name.clone());
+    names.insert(pair.clone(), name.clone());
+    name
+}
+
+impl Default for DeterministicAutomaton {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DeterministicAutomaton {
     pub fn new() -> Self {
         DeterministicAutomaton {
@@ -36,78 +99,104 @@ impl DeterministicAutomaton {
     }
 
     pub fn to_complete_automaton(&mut self) -> DeterministicAutomaton {
-        let sink_state = "sink".to_string();
+        self.complete_with_sink();
+        self.clone()
+    }
 
-        if !self.states.contains(&sink_state) {
-            self.states.insert(sink_state.clone());
+    // a state name that is not used anywhere in the automaton, `base` with primes appended
+    fn fresh_state_name(&self, base: &str) -> String {
+        let used = |name: &String| {
+            self.states.contains(name)
+                || self.start_states.contains(name)
+                || self.terminal_states.contains(name)
+                || self.transitions.iter().any(|((from, _), to)| from == name || to == name)
+        };
+        let mut name = base.to_string();
+        while used(&name) {
+            name.push('\'');
         }
+        name
+    }
+
+    #[doc = r"* adds a fresh sink state and sends every missing transition there
+    * start states and transition targets are declared as states first, so
+      every state of the result has all of its transitions
+    * returns the name of the sink"]
+    fn complete_with_sink(&mut self) -> String {
+        let sink_state = self.fresh_state_name("sink");
+
+        let targets: Vec<String> = self.transitions.values().cloned().collect();
+        self.states.extend(targets);
+        self.states.extend(self.start_states.iter().cloned());
+        self.states.insert(sink_state.clone());
 
         for state in self.states.clone() {
             for symbol in &self.alphabet {
-                if !self.transitions.contains_key(&(state.clone(), symbol.clone())) {
-                    self.transitions.insert((state.clone(), symbol.clone()), sink_state.clone());
-                }
+                self.transitions.entry((state.clone(), symbol.clone())).or_insert_with(|| sink_state.clone());
             }
         }
+        sink_state
+    }
 
-        for symbol in &self.alphabet {
-            self.transitions.insert((sink_state.clone(), symbol.clone()), sink_state.clone());
-        }
-        self.clone()
+    // copy completed over the own alphabet extended with `alphabet`, with the name of its sink
+    fn completed_over(&self, alphabet: &HashSet<String>) -> (DeterministicAutomaton, String) {
+        let mut completed = self.clone();
+        completed.alphabet.extend(alphabet.iter().cloned());
+        let sink_state = completed.complete_with_sink();
+        (completed, sink_state)
     }
 
     #[doc = r"* Gyorgy Matyas
     * gmim2236
     * 1.A.03"]
-    pub fn remove_unreachable_states(&mut self) {
-        let mut reachable_nodes: HashSet<String> = HashSet::new();
-        let mut productive_nodes: Vec<String> = Vec::new();
-        let mut visited: HashSet<String> = HashSet::new();
-        let mut queue = VecDeque::new();
-
-        for node in &self.states {
-            queue.push_back(node.clone());
-            visited.insert(node.clone());
-        }
+    pub fn remove_unreachable_states(&mut self) -> Vec<String> {
+        let reachable = self.reachable_states();
+        self.retain_states(&reachable)
+    }
 
-        while let Some(current_node) = queue.pop_front() {
-            reachable_nodes.insert(current_node.clone());
-            for edge in &self.transitions {
-                if edge.0.0 == current_node && visited.insert(edge.1.clone()) {
-                    queue.push_back(edge.1.clone());
-                }
-            }
-        }
-        for terminal_node in &self.terminal_states {
-            queue.push_back(terminal_node.clone());
-            visited.insert(terminal_node.clone());
-        }
-    
-        while let Some(current_node) = queue.pop_front() {
-            if reachable_nodes.contains(&current_node) {
-                productive_nodes.push(current_node.clone());
-            }
-            for ((from, _), to) in &self.transitions {
-                if to == &current_node && visited.insert(from.clone()) {
-                    queue.push_back(from.clone());
-                }
-            }
-        }
+    #[doc = r"* keeps only the useful states: reachable from the start state and
+    * co-reachable from a terminal state, the language does not change
+    * returns the removed states in sorted order"]
+    pub fn trim(&mut self) -> Vec<String> {
+        let reachable = self.reachable_states();
+        let useful: HashSet<String> = reachable.intersection(&self.productive_states()).cloned().collect();
+        self.retain_states(&useful)
+    }
+
+    // drops every state not in `keep` together with its transitions,
+    // kept states that were only used by transitions become declared states
+    fn retain_states(&mut self, keep: &HashSet<String>) -> Vec<String> {
+        let mut removed: Vec<String> = self.states.difference(keep).cloned().collect();
+        removed.sort();
 
-        self.states = reachable_nodes.clone();
-        self.terminal_states = productive_nodes.into_iter().collect();
-        self.transitions.retain(|(from, _), _| reachable_nodes.contains(from));
+        let used: Vec<String> = self.transitions.iter()
+            .flat_map(|((from, _), to)| [from, to])
+            .filter(|state| keep.contains(*state))
+            .cloned()
+            .collect();
+        self.states.extend(used);
+        self.states.retain(|state| keep.contains(state));
+        self.start_states.retain(|state| keep.contains(state));
+        self.terminal_states.retain(|state| keep.contains(state));
+        self.transitions.retain(|(from, _), to| keep.contains(from) && keep.contains(to));
+        removed
     }
-    
+
     #[doc = r"* Gyorgy Matyas
     * gmim2236
     * 1.B.03
     * pda"]
     pub fn minimize(&self) -> DeterministicAutomaton {
+        self.useful_part().refine_partition()
+    }
+
+    // set based partition refinement, every state of self is kept
+    fn refine_partition(&self) -> DeterministicAutomaton {
         let mut partition: Vec<HashSet<String>> = vec![
             self.terminal_states.clone(), 
             self.states.difference(&self.terminal_states).cloned().collect()
         ];
+        partition.retain(|block| !block.is_empty());
     
         let mut worklist: Vec<HashSet<String>> = partition.clone();
     
@@ -117,7 +206,7 @@ impl DeterministicAutomaton {
             for c in &self.alphabet {
                 let x: HashSet<String> = self.states.iter()
                     .filter(|&state| self.transitions.get(&(state.clone(), c.clone()))
-                    .map_or(false, |next| a.contains(next)))
+                    .is_some_and(|next| a.contains(next)))
                     .cloned()
                     .collect();
 
@@ -157,9 +246,9 @@ impl DeterministicAutomaton {
         let mut state_mapping: HashMap<String, String> = HashMap::new();
     
         for block in &partition {
-            let representative = block.iter().next().unwrap();
+            let representative = block.iter().min().unwrap();
             minimized.states.insert(representative.clone());
-            if self.start_states.contains(representative) {
+            if block.iter().any(|state| self.start_states.contains(state)) {
                 minimized.start_states.insert(representative.clone());
             }
             if self.terminal_states.contains(representative) {
@@ -172,7 +261,7 @@ impl DeterministicAutomaton {
                     if let Some(next_state) = self.transitions.get(&(state.clone(), symbol.clone())) {
                         let next_block = partition.iter()
                             .find(|b| b.contains(next_state)).unwrap();
-                        let next_representative = next_block.iter().next().unwrap();
+                        let next_representative = next_block.iter().min().unwrap();
                         minimized.transitions.insert((representative.clone(), symbol.clone()), next_representative.clone());
                     }
                 }
@@ -180,7 +269,7 @@ impl DeterministicAutomaton {
         }
     
         if minimized.start_states.is_empty() {
-            if let Some(first_representative) = minimized.states.difference(&minimized.terminal_states).cloned().next() {
+            if let Some(first_representative) = minimized.states.difference(&minimized.terminal_states).next().cloned() {
                 minimized.start_states.insert(first_representative.clone());
             }
         }
@@ -188,15 +277,339 @@ impl DeterministicAutomaton {
         minimized
     }
 
-    pub fn is_minimized(&self) -> (bool, DeterministicAutomaton) {
-        let minim = self.minimize();
-        if minim.transitions.eq(&self.transitions) && minim.states.eq(&self.states) 
-            && minim.terminal_states.eq(&self.terminal_states) && minim.alphabet.eq(&self.alphabet) {
-            return (true, self.clone());
+    pub fn is_minimized(&self) -> MinimizationReport {
+        self.minimization_report()
+    }
+
+    // reachable states in bfs order from the start state, symbols sorted
+    fn canonical_order(&self) -> Vec<String> {
+        let mut symbols: Vec<&String> = self.alphabet.iter().collect();
+        symbols.sort();
+
+        let mut order: Vec<String> = self.start_states.iter().min().cloned().into_iter().collect();
+        let mut visited: HashSet<String> = order.iter().cloned().collect();
+        let mut index = 0;
+        while index < order.len() {
+            for symbol in &symbols {
+                if let Some(next) = self.transitions.get(&(order[index].clone(), symbol.to_string())) {
+                    if visited.insert(next.clone()) {
+                        order.push(next.clone());
+                    }
+                }
+            }
+            index += 1;
+        }
+        order
+    }
+
+    #[doc = r"* renames the reachable states to 0, 1, 2, ... in bfs order
+    * from the start state over the sorted alphabet, unreachable states are dropped
+    * two automata have the same canonical form iff they are isomorphic"]
+    pub fn canonical_form(&self) -> DeterministicAutomaton {
+        let order = self.canonical_order();
+        let names: HashMap<&String, String> = order.iter().enumerate().map(|(index, state)| (state, index.to_string())).collect();
+
+        let mut canonical = DeterministicAutomaton::new();
+        canonical.alphabet = self.alphabet.clone();
+        canonical.states = names.values().cloned().collect();
+        canonical.start_states = order.first().map(|start| names[start].clone()).into_iter().collect();
+        canonical.terminal_states = order.iter()
+            .filter(|state| self.terminal_states.contains(*state))
+            .map(|state| names[state].clone())
+            .collect();
+        for ((from, symbol), to) in &self.transitions {
+            if let (Some(from), Some(to)) = (names.get(from), names.get(to)) {
+                canonical.transitions.insert((from.clone(), symbol.clone()), to.clone());
+            }
+        }
+        canonical
+    }
+
+    #[doc = r"* isomorphism of the reachable parts
+    * returns the bijection from the states of self to the states of other,
+      None if the automata are not isomorphic"]
+    pub fn is_isomorphic(&self, other: &DeterministicAutomaton) -> Option<HashMap<String, String>> {
+        if self.alphabet != other.alphabet {
+            return None;
+        }
+        let (order, other_order) = (self.canonical_order(), other.canonical_order());
+        if order.len() != other_order.len() {
+            return None;
+        }
+
+        let bijection: HashMap<String, String> = order.iter().cloned().zip(other_order.iter().cloned()).collect();
+        for (state, other_state) in &bijection {
+            if self.terminal_states.contains(state) != other.terminal_states.contains(other_state) {
+                return None;
+            }
+            for symbol in &self.alphabet {
+                let next = self.transitions.get(&(state.clone(), symbol.clone())).map(|next| &bijection[next]);
+                if next != other.transitions.get(&(other_state.clone(), symbol.clone())) {
+                    return None;
+                }
+            }
+        }
+        Some(bijection)
+    }
+
+    pub fn accepts(&self, word: &str) -> bool {
+        self.run(word).accepted
+    }
+
+    pub fn run(&self, word: &str) -> DeterministicRun {
+        self.run_symbols(&split_word(word, &self.alphabet))
+    }
+
+    pub fn run_symbols(&self, word: &[String]) -> DeterministicRun {
+        let Some(start) = self.start_states.iter().min() else {
+            return DeterministicRun { states: Vec::new(), stuck_at: Some(0), accepted: false };
+        };
+
+        let mut states = vec![start.clone()];
+        for (index, symbol) in word.iter().enumerate() {
+            let current = states.last().unwrap();
+            match self.transitions.get(&(current.clone(), symbol.clone())) {
+                Some(next) => states.push(next.clone()),
+                None => return DeterministicRun { states, stuck_at: Some(index), accepted: false },
+            }
+        }
+
+        let accepted = self.terminal_states.contains(states.last().unwrap());
+        DeterministicRun { states, stuck_at: None, accepted }
+    }
+
+    pub fn check_for_file(&self, file_name: &str) -> io::Result<()> {
+        let file = File::open(file_name)?;
+        for line in io::BufReader::new(file).lines() {
+            let line = line?;
+            if self.accepts(&line) {
+                println!("{} accepted", &line);
+            } else {
+                println!("{} declined", &line);
+            }
+        }
+        Ok(())
+    }
+
+    // states reachable from the start states
+    fn reachable_states(&self) -> HashSet<String> {
+        let mut successors: HashMap<&String, Vec<&String>> = HashMap::new();
+        for ((from, _), to) in &self.transitions {
+            successors.entry(from).or_default().push(to);
         }
-        else {
-            return (false, minim);
+        Self::search_from(&self.start_states, &successors)
+    }
+
+    // states from which a terminal state is reachable
+    fn productive_states(&self) -> HashSet<String> {
+        let mut predecessors: HashMap<&String, Vec<&String>> = HashMap::new();
+        for ((from, _), to) in &self.transitions {
+            predecessors.entry(to).or_default().push(from);
         }
+        Self::search_from(&self.terminal_states, &predecessors)
+    }
+
+    // breadth first search over an adjacency map built once, so it is linear
+    fn search_from(sources: &HashSet<String>, adjacent: &HashMap<&String, Vec<&String>>) -> HashSet<String> {
+        let mut visited: HashSet<String> = sources.clone();
+        let mut queue: VecDeque<&String> = sources.iter().collect();
+
+        while let Some(current) = queue.pop_front() {
+            for &next in adjacent.get(current).into_iter().flatten() {
+                if visited.insert(next.clone()) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        visited
+    }
+
+    #[doc = r"* product construction
+    * both automata are completed over the union of the alphabets, a missing
+      start state is the sink, the result only keeps the reachable and
+      productive (p,q) pairs"]
+    pub fn product(&self, other: &DeterministicAutomaton, operation: ProductOperation) -> DeterministicAutomaton {
+        let alphabet: HashSet<String> = self.alphabet.union(&other.alphabet).cloned().collect();
+        let mut symbols: Vec<String> = alphabet.iter().cloned().collect();
+        symbols.sort();
+
+        let (left, left_sink) = self.completed_over(&alphabet);
+        let (right, right_sink) = other.completed_over(&alphabet);
+
+        let mut product = DeterministicAutomaton::new();
+        product.alphabet = alphabet;
+
+        // a missing start state behaves like the sink
+        let left_start = left.start_states.iter().min().unwrap_or(&left_sink).clone();
+        let right_start = right.start_states.iter().min().unwrap_or(&right_sink).clone();
+
+        let mut names: HashMap<(String, String), String> = HashMap::new();
+        let start = (left_start, right_start);
+        let start_name = intern_pair(&mut names, &mut product.states, &start);
+        product.start_states.insert(start_name);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some((p, q)) = queue.pop_front() {
+            let name = names[&(p.clone(), q.clone())].clone();
+            if operation.accepts(left.terminal_states.contains(&p), right.terminal_states.contains(&q)) {
+                product.terminal_states.insert(name.clone());
+            }
+
+            for symbol in &symbols {
+                let next_p = left.transitions.get(&(p.clone(), symbol.clone())).unwrap_or(&left_sink).clone();
+                let next_q = right.transitions.get(&(q.clone(), symbol.clone())).unwrap_or(&right_sink).clone();
+                let next = (next_p, next_q);
+                let next_name = match names.get(&next) {
+                    Some(next_name) => next_name.clone(),
+                    None => {
+                        let next_name = intern_pair(&mut names, &mut product.states, &next);
+                        queue.push_back(next);
+                        next_name
+                    }
+                };
+                product.transitions.insert((name.clone(), symbol.clone()), next_name);
+            }
+        }
+
+        // dropping the dead pairs
+        product.trim();
+
+        product
+    }
+
+    pub fn intersection(&self, other: &DeterministicAutomaton) -> DeterministicAutomaton {
+        self.product(other, ProductOperation::Intersection)
+    }
+
+    pub fn union(&self, other: &DeterministicAutomaton) -> DeterministicAutomaton {
+        self.product(other, ProductOperation::Union)
+    }
+
+    pub fn difference(&self, other: &DeterministicAutomaton) -> DeterministicAutomaton {
+        self.product(other, ProductOperation::Difference)
+    }
+
+    pub fn symmetric_difference(&self, other: &DeterministicAutomaton) -> DeterministicAutomaton {
+        self.product(other, ProductOperation::SymmetricDifference)
+    }
+
+    #[doc = r"* pairwise table exploration over the completed automata (bfs)
+    * returns the shortest word leading to a pair where `found` holds for the
+      (left accepts, right accepts) flags, together with those flags"]
+    fn find_pair_word(&self, other: &DeterministicAutomaton, found: impl Fn(bool, bool) -> bool) -> Option<(Vec<String>, bool, bool)> {
+        let alphabet: HashSet<String> = self.alphabet.union(&other.alphabet).cloned().collect();
+        let mut symbols: Vec<String> = alphabet.iter().cloned().collect();
+        symbols.sort();
+
+        let (left, left_sink) = self.completed_over(&alphabet);
+        let (right, right_sink) = other.completed_over(&alphabet);
+
+        // a missing start state behaves like the sink
+        let left_start = left.start_states.iter().min().unwrap_or(&left_sink).clone();
+        let right_start = right.start_states.iter().min().unwrap_or(&right_sink).clone();
+
+        let mut table: Vec<(String, String)> = vec![(left_start, right_start)];
+        let mut parents: Vec<Option<(usize, String)>> = vec![None];
+        let mut indices: HashMap<(String, String), usize> = HashMap::new();
+        indices.insert(table[0].clone(), 0);
+
+        let mut table_index = 0;
+        while table_index < table.len() {
+            let (q, q_prime) = table[table_index].clone();
+            let accepted = (left.terminal_states.contains(&q), right.terminal_states.contains(&q_prime));
+
+            if found(accepted.0, accepted.1) {
+                // walking back on the parent pointers
+                let mut word = Vec::new();
+                let mut current = table_index;
+                while let Some((parent, symbol)) = &parents[current] {
+                    word.push(symbol.clone());
+                    current = *parent;
+                }
+                word.reverse();
+                return Some((word, accepted.0, accepted.1));
+            }
+
+            for a in &symbols {
+                let next = (
+                    left.transitions.get(&(q.clone(), a.clone())).unwrap_or(&left_sink).clone(),
+                    right.transitions.get(&(q_prime.clone(), a.clone())).unwrap_or(&right_sink).clone(),
+                );
+                if !indices.contains_key(&next) {
+                    indices.insert(next.clone(), table.len());
+                    table.push(next);
+                    parents.push(Some((table_index, a.clone())));
+                }
+            }
+            table_index += 1;
+        }
+
+        None
+    }
+
+    #[doc = r"* None if the two automata are equivalent, otherwise the shortest
+    * word accepted by exactly one of them"]
+    pub fn equivalence_witness(&self, other: &DeterministicAutomaton) -> Option<EquivalenceWitness> {
+        self.find_pair_word(other, |left, right| left != right)
+            .map(|(word, left_accepts, _)| EquivalenceWitness {
+                word,
+                accepted_by: if left_accepts { AcceptingSide::Left } else { AcceptingSide::Right },
+            })
+    }
+
+    #[doc = r"* L(self) ⊆ L(other) check
+    * on failure returns the shortest word in L(self) \ L(other)"]
+    pub fn check_inclusion(&self, other: &DeterministicAutomaton) -> Result<(), Vec<String>> {
+        match self.find_pair_word(other, |left, right| left && !right) {
+            Some((word, _, _)) => Err(word),
+            None => Ok(()),
+        }
+    }
+
+    pub fn is_subset_of(&self, other: &DeterministicAutomaton) -> bool {
+        self.check_inclusion(other).is_ok()
+    }
+
+    pub fn complement(&self) -> DeterministicAutomaton {
+        self.complement_over(&self.alphabet)
+    }
+
+    #[doc = r"* complement relative to the given alphabet (extended with the own one)
+    * the automaton is completed with a sink, then the terminal states are swapped"]
+    pub fn complement_over(&self, alphabet: &HashSet<String>) -> DeterministicAutomaton {
+        let (mut complement, sink_state) = self.completed_over(alphabet);
+
+        // without a start state the language is empty, so the complement starts in the sink
+        if complement.start_states.is_empty() {
+            complement.start_states.insert(sink_state);
+        }
+
+        complement.remove_unreachable_states();
+        complement.terminal_states = complement.states.difference(&self.terminal_states).cloned().collect();
+
+        complement
+    }
+
+    pub fn to_nondeterministic(&self) -> NonDeterministicAutomaton {
+        let mut ndfa = NonDeterministicAutomaton::new();
+        ndfa.states = self.states.clone();
+        ndfa.alphabet = self.alphabet.clone();
+        ndfa.start_states = self.start_states.clone();
+        ndfa.terminal_states = self.terminal_states.clone();
+        for ((from, symbol), to) in &self.transitions {
+            ndfa.add_transition(from.clone(), symbol.clone(), to.clone());
+        }
+        ndfa
+    }
+
+    pub fn to_regex(&self) -> Regex {
+        let transitions = self.transitions.iter()
+            .map(|((from, symbol), to)| {
+                let label = if symbol == "eps" { Regex::Epsilon } else { Regex::Symbol(symbol.clone()) };
+                (from.clone(), label, to.clone())
+            })
+            .collect();
+        eliminate_states(&self.states, &self.start_states, &self.terminal_states, transitions)
     }
     
 }
@@ -209,32 +622,39 @@ impl Automaton for DeterministicAutomaton {
              fontsize=\"16\"];\n    fontsize=\"10\";\n    compound=true;\n\n"
         );
 
-        for state in &self.states {
-            if self.start_states.contains(state) {
-                out_dot_code.push_str(&format!("    i{} [shape=point, style=invis];\n", state));
+        // sorted output, so that canonical forms give stable files
+        let mut states: Vec<&String> = self.states.iter().collect();
+        states.sort();
+
+        for state in &states {
+            if self.start_states.contains(*state) {
+                out_dot_code.push_str(&format!("    {} [shape=point, style=invis];\n", dot_id(&format!("i{}", state))));
             }
         }
 
-        for state in &self.states {
-            if self.terminal_states.contains(state) {
-                out_dot_code.push_str(&format!("    {} [shape=doublecircle];\n", state));
+        for state in &states {
+            if self.terminal_states.contains(*state) {
+                out_dot_code.push_str(&format!("    {} [shape=doublecircle];\n", dot_id(state)));
             }
         }
-        out_dot_code.push_str("\n");
+        out_dot_code.push('\n');
 
-        for state in &self.start_states {
-            out_dot_code.push_str(&format!("    i{} -> {};\n", state, state));
+        for state in &states {
+            if self.start_states.contains(*state) {
+                out_dot_code.push_str(&format!("    {} -> {};\n", dot_id(&format!("i{}", state)), dot_id(state)));
+            }
         }
 
-        let mut edge_map: HashMap<(&str, &str), Vec<&str>> = HashMap::new();
+        let mut edge_map: BTreeMap<(&str, &str), Vec<&str>> = BTreeMap::new();
         for ((from, input), to) in &self.transitions {
             let key = (from.as_str(), to.as_str());
             edge_map.entry(key).or_default().push(input.as_str());
         }
 
-        for ((start, end), labels) in edge_map {
+        for ((start, end), mut labels) in edge_map {
+            labels.sort();
             let label_str = labels.join(", ");
-            out_dot_code.push_str(&format!("    {} -> {} [label=\"{}\"];\n", start, end, label_str));
+            out_dot_code.push_str(&format!("    {} -> {} [label=\"{}\"];\n", dot_id(start), dot_id(end), escape_dot_label(&label_str)));
         }
 
         out_dot_code.push_str("}\n");
@@ -242,27 +662,43 @@ impl Automaton for DeterministicAutomaton {
     }
 
 
-    fn build_from_file(&mut self, file_name: &str) ->io::Result<()> {
-        let path = Path::new(file_name);
-        let file = File::open(&path)?;
-        let lines = io::BufReader::new(file).lines().collect::<Result<Vec<String>, _>>().expect("couldnt read from file");
+    fn build_from_file(&mut self, file_name: &str) -> Result<(), AutomatonError> {
+        let lines = SourceLines::read(file_name)?;
 
-        self.states = lines[0].split_whitespace().map(String::from).collect();
-        self.alphabet = lines[1].split_whitespace().map(String::from).collect();
-        self.start_states = lines[2].split_whitespace().map(String::from).collect();
-        self.terminal_states = lines[3].split_whitespace().map(String::from).collect();
-        self.transitions.clear();
-        
-        for line in &lines[4..] {
-            let parts: Vec<String> = line.split_whitespace().map(String::from).collect();
-            if parts.len() == 3 {
-                let from_state = parts[0].clone();
-                let input = parts[1].clone();
-                let to_state = parts[2].clone();
-                self.transitions.insert((from_state, input), to_state);
+        let states: HashSet<String> = lines.section(0, "states")?.into_iter().map(|(_, state)| state).collect();
+        let alphabet: HashSet<String> = lines.section(1, "alphabet")?.into_iter().map(|(_, symbol)| symbol).collect();
+        let start_states = lines.state_section(2, "start states", &states)?;
+        let terminal_states = lines.state_section(3, "terminal states", &states)?;
+
+        let mut transitions = HashMap::new();
+        for index in 4..lines.len() {
+            let parts = lines.tokens(index);
+            if parts.is_empty() {
+                continue;
+            }
+            let [(from_column, from_state), (input_column, input), (to_column, to_state)] = parts.as_slice() else {
+                return Err(AutomatonError::InvalidTransition {
+                    location: lines.location(index, parts[0].0),
+                    description: format!("expected `from symbol to`, found {} fields", parts.len()),
+                });
+            };
+            lines.check_state(index, *from_column, from_state, &states)?;
+            lines.check_symbol(index, *input_column, input, &alphabet)?;
+            lines.check_state(index, *to_column, to_state, &states)?;
+            if transitions.insert((from_state.clone(), input.clone()), to_state.clone()).is_some() {
+                return Err(AutomatonError::DuplicateTransition {
+                    location: lines.location(index, *from_column),
+                    state: from_state.clone(),
+                    symbol: input.clone(),
+                });
             }
         }
 
+        self.states = states;
+        self.alphabet = alphabet;
+        self.start_states = start_states;
+        self.terminal_states = terminal_states;
+        self.transitions = transitions;
         Ok(())
     }
 
@@ -281,37 +717,6 @@ impl fmt::Display for DeterministicAutomaton {
     * pda"]
 impl PartialEq for DeterministicAutomaton {
     fn eq(&self, other: &Self) -> bool {
-        let mut self_copy = self.clone();
-        let mut other_copy = other.clone();
-        self_copy.to_complete_automaton();
-        other_copy.to_complete_automaton();
-        let mut table: Vec<(String, String)> = vec![(self_copy.start_states.iter().next().unwrap().clone(), 
-                                                    other_copy.start_states.iter().next().unwrap().clone())];
-        if self_copy.terminal_states.contains(&table[0].0) != other_copy.terminal_states.contains(&table[0].1) {
-            return false;
-        }
-        let mut table_index = 0;
-        while table_index < table.len() {
-            
-            let (q, q_prime) = &table[table_index].clone();
-            table_index += 1;
-            for a in &self_copy.alphabet {
-                let next_q = self_copy.transitions.get(&(q.clone(), a.clone()));
-                let next_q_prime = other_copy.transitions.get(&(q_prime.clone(), a.clone()));
-                if let (Some(next_q), Some(next_q_prime)) = (next_q, next_q_prime) {
-                    if self_copy.terminal_states.contains(next_q) != other_copy.terminal_states.contains(next_q_prime) {
-                        return false;
-                    }
-
-                    if !table.contains(&(next_q.clone(), next_q_prime.clone())) {
-                        table.push((next_q.clone(), next_q_prime.clone()));
-                    }
-                } else {
-                    return false;
-                }
-            }
-        }
-                                                
-        true
+        self.equivalence_witness(other).is_none()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,221 @@
+/*
+ * errors while loading automaton files or building them in code
+ * load errors know the file, and where possible the line and column
+ */
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+
+// 1-based position in an automaton file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+#[derive(Debug)]
+pub enum AutomatonError {
+    Io { file: String, source: io::Error },
+    MissingSection { location: SourceLocation, section: String },
+    InvalidHeader { location: SourceLocation, description: String },
+    UnknownState { location: SourceLocation, state: String },
+    UnknownSymbol { location: SourceLocation, symbol: String },
+    DuplicateTransition { location: SourceLocation, state: String, symbol: String },
+    InvalidTransition { location: SourceLocation, description: String },
+    InvalidProduction { location: SourceLocation, description: String },
+}
+
+impl AutomatonError {
+    pub fn file(&self) -> &str {
+        match self {
+            AutomatonError::Io { file, .. } => file,
+            _ => &self.location().unwrap().file,
+        }
+    }
+
+    // None for io errors, they do not belong to a line
+    pub fn location(&self) -> Option<&SourceLocation> {
+        match self {
+            AutomatonError::Io { .. } => None,
+            AutomatonError::MissingSection { location, .. }
+            | AutomatonError::InvalidHeader { location, .. }
+            | AutomatonError::UnknownState { location, .. }
+            | AutomatonError::UnknownSymbol { location, .. }
+            | AutomatonError::DuplicateTransition { location, .. }
+            | AutomatonError::InvalidTransition { location, .. }
+            | AutomatonError::InvalidProduction { location, .. } => Some(location),
+        }
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.location().map(|location| location.line)
+    }
+
+    pub fn column(&self) -> Option<usize> {
+        self.location().map(|location| location.column)
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            AutomatonError::Io { source, .. } => source.to_string(),
+            AutomatonError::MissingSection { section, .. } => format!("missing section: {}", section),
+            AutomatonError::InvalidHeader { description, .. } => format!("invalid header: {}", description),
+            AutomatonError::UnknownState { state, .. } => format!("unknown state: {}", state),
+            AutomatonError::UnknownSymbol { symbol, .. } => format!("unknown symbol: {}", symbol),
+            AutomatonError::DuplicateTransition { state, symbol, .. } => {
+                format!("duplicate transition from {} on {}", state, symbol)
+            }
+            AutomatonError::InvalidTransition { description, .. } => format!("invalid transition: {}", description),
+            AutomatonError::InvalidProduction { description, .. } => format!("invalid production: {}", description),
+        }
+    }
+}
+
+impl fmt::Display for AutomatonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.location() {
+            Some(location) => write!(f, "{}: {}", location, self.description()),
+            None => write!(f, "{}: {}", self.file(), self.description()),
+        }
+    }
+}
+
+impl Error for AutomatonError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AutomatonError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+// so that `?` keeps working in functions returning io::Result
+impl From<AutomatonError> for io::Error {
+    fn from(error: AutomatonError) -> Self {
+        match error {
+            AutomatonError::Io { source, .. } => source,
+            error => io::Error::new(io::ErrorKind::InvalidData, error.to_string()),
+        }
+    }
+}
+
+// invalid automaton given to PushdownAutomatonBuilder::build
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PushdownBuildError {
+    MissingStartState,
+    MissingStackStartSymbol,
+    UnknownState(String),
+    UnknownInputSymbol(String),
+    UnknownStackSymbol(String),
+    // "eps" can not be an input or stack symbol
+    ReservedSymbol(String),
+}
+
+impl fmt::Display for PushdownBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PushdownBuildError::MissingStartState => write!(f, "missing start state"),
+            PushdownBuildError::MissingStackStartSymbol => write!(f, "missing stack start symbol"),
+            PushdownBuildError::UnknownState(state) => write!(f, "unknown state: {}", state),
+            PushdownBuildError::UnknownInputSymbol(symbol) => write!(f, "unknown input symbol: {}", symbol),
+            PushdownBuildError::UnknownStackSymbol(symbol) => write!(f, "unknown stack symbol: {}", symbol),
+            PushdownBuildError::ReservedSymbol(symbol) => write!(f, "reserved symbol: {}", symbol),
+        }
+    }
+}
+
+impl Error for PushdownBuildError {}
+
+// lines of an automaton file, split into whitespace separated tokens with their columns
+pub(crate) struct SourceLines {
+    file: String,
+    lines: Vec<String>,
+}
+
+impl SourceLines {
+    pub(crate) fn read(file_name: &str) -> Result<Self, AutomatonError> {
+        let content = fs::read_to_string(file_name).map_err(|source| AutomatonError::Io {
+            file: file_name.to_string(),
+            source,
+        })?;
+        Ok(SourceLines {
+            file: file_name.to_string(),
+            lines: content.lines().map(String::from).collect(),
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    // index is 0-based, the location is 1-based
+    pub(crate) fn location(&self, index: usize, column: usize) -> SourceLocation {
+        SourceLocation {
+            file: self.file.clone(),
+            line: index + 1,
+            column,
+        }
+    }
+
+    pub(crate) fn tokens(&self, index: usize) -> Vec<(usize, String)> {
+        let line = &self.lines[index];
+        let mut tokens = Vec::new();
+        let mut current: Option<(usize, String)> = None;
+        for (column, character) in line.chars().enumerate() {
+            if character.is_whitespace() {
+                tokens.extend(current.take());
+            } else {
+                current.get_or_insert_with(|| (column + 1, String::new())).1.push(character);
+            }
+        }
+        tokens.extend(current);
+        tokens
+    }
+
+    // a header line, error if the file is shorter
+    pub(crate) fn section(&self, index: usize, section: &str) -> Result<Vec<(usize, String)>, AutomatonError> {
+        if index >= self.lines.len() {
+            return Err(AutomatonError::MissingSection {
+                location: self.location(self.lines.len(), 1),
+                section: section.to_string(),
+            });
+        }
+        Ok(self.tokens(index))
+    }
+
+    pub(crate) fn check_state(&self, index: usize, column: usize, state: &str, states: &HashSet<String>) -> Result<(), AutomatonError> {
+        if states.contains(state) {
+            Ok(())
+        } else {
+            Err(AutomatonError::UnknownState { location: self.location(index, column), state: state.to_string() })
+        }
+    }
+
+    pub(crate) fn check_symbol(&self, index: usize, column: usize, symbol: &str, symbols: &HashSet<String>) -> Result<(), AutomatonError> {
+        if symbols.contains(symbol) {
+            Ok(())
+        } else {
+            Err(AutomatonError::UnknownSymbol { location: self.location(index, column), symbol: symbol.to_string() })
+        }
+    }
+
+    // a header line of state names, every one has to be a known state
+    pub(crate) fn state_section(&self, index: usize, section: &str, states: &HashSet<String>) -> Result<HashSet<String>, AutomatonError> {
+        let mut result = HashSet::new();
+        for (column, token) in self.section(index, section)? {
+            self.check_state(index, column, &token, states)?;
+            result.insert(token);
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,290 @@
+/*
+ * context free grammars
+ * file format: nonterminals, terminals, start symbol, then one line per rule
+ *     S -> a S b | eps
+ * symbols are separated by spaces, eps is the empty word
+ */
+
+use std::collections::{HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+use crate::error::{AutomatonError, SourceLines};
+
+// head -> body, an empty body is the empty word
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Production {
+    pub head: String,
+    pub body: Vec<String>,
+}
+
+impl Production {
+    // body as written in the files
+    fn body_text(&self) -> String {
+        if self.body.is_empty() {
+            "eps".to_string()
+        } else {
+            self.body.join(" ")
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GrammarError {
+    UnknownStartSymbol(String),
+    UnknownSymbol(String),
+    // production head that is not a nonterminal
+    NotANonterminal(String),
+    // symbol that is a terminal and a nonterminal at the same time
+    AmbiguousSymbol(String),
+    // "eps", "->" and "|" can not be symbols
+    ReservedSymbol(String),
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GrammarError::UnknownStartSymbol(symbol) => write!(f, "start symbol is not a nonterminal: {}", symbol),
+            GrammarError::UnknownSymbol(symbol) => write!(f, "unknown symbol: {}", symbol),
+            GrammarError::NotANonterminal(symbol) => write!(f, "production head is not a nonterminal: {}", symbol),
+            GrammarError::AmbiguousSymbol(symbol) => write!(f, "symbol is a terminal and a nonterminal: {}", symbol),
+            GrammarError::ReservedSymbol(symbol) => write!(f, "reserved symbol: {}", symbol),
+        }
+    }
+}
+
+impl Error for GrammarError {}
+
+impl GrammarError {
+    // the symbol the error is about
+    pub fn symbol(&self) -> &str {
+        match self {
+            GrammarError::UnknownStartSymbol(symbol)
+            | GrammarError::UnknownSymbol(symbol)
+            | GrammarError::NotANonterminal(symbol)
+            | GrammarError::AmbiguousSymbol(symbol)
+            | GrammarError::ReservedSymbol(symbol) => symbol,
+        }
+    }
+}
+
+const RESERVED_SYMBOLS: [&str; 3] = ["eps", "->", "|"];
+
+// the checks of the two symbol sets, shared by validate and the file reader
+fn check_symbol_sets(nonterminals: &HashSet<String>, terminals: &HashSet<String>) -> Result<(), GrammarError> {
+    let reserved = nonterminals.iter().chain(terminals).filter(|symbol| RESERVED_SYMBOLS.contains(&symbol.as_str())).min();
+    if let Some(symbol) = reserved {
+        return Err(GrammarError::ReservedSymbol(symbol.clone()));
+    }
+    if let Some(symbol) = nonterminals.intersection(terminals).min() {
+        return Err(GrammarError::AmbiguousSymbol(symbol.clone()));
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+pub struct ContextFreeGrammar {
+    pub nonterminals: HashSet<String>,
+    pub terminals: HashSet<String>,
+    pub start_symbol: String,
+    pub productions: Vec<Production>,
+}
+
+impl Default for ContextFreeGrammar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContextFreeGrammar {
+    pub fn new() -> Self {
+        ContextFreeGrammar {
+            nonterminals: HashSet::new(),
+            terminals: HashSet::new(),
+            start_symbol: String::new(),
+            productions: Vec::new(),
+        }
+    }
+
+    // duplicates are kept only once
+    pub fn add_production(&mut self, head: &str, body: Vec<String>) {
+        let production = Production { head: head.to_string(), body };
+        if !self.productions.contains(&production) {
+            self.productions.push(production);
+        }
+    }
+
+    pub fn productions_of<'a>(&'a self, head: &'a str) -> impl Iterator<Item = &'a Production> + 'a {
+        self.productions.iter().filter(move |production| production.head == head)
+    }
+
+    // checks that the grammar is well formed, the first problem is returned
+    pub fn validate(&self) -> Result<(), GrammarError> {
+        check_symbol_sets(&self.nonterminals, &self.terminals)?;
+        if !self.nonterminals.contains(&self.start_symbol) {
+            return Err(GrammarError::UnknownStartSymbol(self.start_symbol.clone()));
+        }
+
+        for production in &self.productions {
+            if !self.nonterminals.contains(&production.head) {
+                return Err(GrammarError::NotANonterminal(production.head.clone()));
+            }
+            for symbol in &production.body {
+                if !self.nonterminals.contains(symbol) && !self.terminals.contains(symbol) {
+                    return Err(GrammarError::UnknownSymbol(symbol.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[doc = r"* nonterminals that derive no terminal word, sorted
+    * fixpoint: a nonterminal is productive if one of its bodies has only
+      terminals and productive nonterminals"]
+    pub fn unproductive_nonterminals(&self) -> Vec<String> {
+        let mut productive: HashSet<&String> = HashSet::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in &self.productions {
+                if !productive.contains(&production.head)
+                    && production.body.iter().all(|symbol| self.terminals.contains(symbol) || productive.contains(symbol))
+                {
+                    productive.insert(&production.head);
+                    changed = true;
+                }
+            }
+        }
+
+        let mut unproductive: Vec<String> = self.nonterminals.iter().filter(|symbol| !productive.contains(symbol)).cloned().collect();
+        unproductive.sort();
+        unproductive
+    }
+
+    // nonterminals that do not appear in any sentential form, sorted
+    pub fn unreachable_nonterminals(&self) -> Vec<String> {
+        let mut reachable: HashSet<&String> = HashSet::from([&self.start_symbol]);
+        let mut queue: VecDeque<&String> = VecDeque::from([&self.start_symbol]);
+        while let Some(head) = queue.pop_front() {
+            for production in self.productions_of(head) {
+                for symbol in &production.body {
+                    if self.nonterminals.contains(symbol) && reachable.insert(symbol) {
+                        queue.push_back(symbol);
+                    }
+                }
+            }
+        }
+
+        let mut unreachable: Vec<String> = self.nonterminals.iter().filter(|symbol| !reachable.contains(symbol)).cloned().collect();
+        unreachable.sort();
+        unreachable
+    }
+
+    #[doc = r"* reads the grammar file format, see the top of this file
+    * a nonterminal can have several rule lines, their alternatives are merged"]
+    pub fn build_from_file(&mut self, file_name: &str) -> Result<(), AutomatonError> {
+        let lines = SourceLines::read(file_name)?;
+
+        let nonterminal_section = lines.section(0, "nonterminals")?;
+        let terminal_section = lines.section(1, "terminals")?;
+        let nonterminals: HashSet<String> = nonterminal_section.iter().map(|(_, symbol)| symbol.clone()).collect();
+        let terminals: HashSet<String> = terminal_section.iter().map(|(_, symbol)| symbol.clone()).collect();
+        if let Err(error) = check_symbol_sets(&nonterminals, &terminals) {
+            // the terminal line first, that is where a symbol of both lines is repeated
+            let (index, column) = [(1, &terminal_section), (0, &nonterminal_section)]
+                .into_iter()
+                .find_map(|(index, section)| {
+                    section.iter().find(|(_, symbol)| symbol == error.symbol()).map(|(column, _)| (index, *column))
+                })
+                .unwrap_or((0, 1));
+            return Err(AutomatonError::InvalidHeader { location: lines.location(index, column), description: error.to_string() });
+        }
+        let Some((start_column, start_symbol)) = lines.section(2, "start symbol")?.into_iter().next() else {
+            return Err(AutomatonError::MissingSection { location: lines.location(2, 1), section: "start symbol".to_string() });
+        };
+        lines.check_symbol(2, start_column, &start_symbol, &nonterminals)?;
+
+        let mut grammar = ContextFreeGrammar {
+            nonterminals,
+            terminals,
+            start_symbol,
+            productions: Vec::new(),
+        };
+        let mut symbols: HashSet<String> = grammar.nonterminals.union(&grammar.terminals).cloned().collect();
+        symbols.insert("eps".to_string());
+
+        for index in 3..lines.len() {
+            let tokens = lines.tokens(index);
+            let Some((head_column, head)) = tokens.first() else {
+                continue;
+            };
+            if tokens.get(1).map(|(_, arrow)| arrow.as_str()) != Some("->") {
+                return Err(AutomatonError::InvalidProduction {
+                    location: lines.location(index, *head_column),
+                    description: "expected `head -> body | body ...`".to_string(),
+                });
+            }
+            lines.check_symbol(index, *head_column, head, &grammar.nonterminals)?;
+
+            for alternative in tokens[2..].split(|(_, symbol)| symbol == "|") {
+                // eps alone is the empty word, elsewhere it is not allowed
+                let is_eps = alternative.len() == 1 && alternative[0].1 == "eps";
+                if alternative.is_empty() || (!is_eps && alternative.iter().any(|(_, symbol)| symbol == "eps")) {
+                    let column = alternative.first().map_or(*head_column, |(column, _)| *column);
+                    return Err(AutomatonError::InvalidProduction {
+                        location: lines.location(index, column),
+                        description: "empty alternative, or eps next to other symbols".to_string(),
+                    });
+                }
+
+                let mut body = Vec::new();
+                for (column, symbol) in alternative {
+                    lines.check_symbol(index, *column, symbol, &symbols)?;
+                    if !is_eps {
+                        body.push(symbol.clone());
+                    }
+                }
+                grammar.add_production(head, body);
+            }
+        }
+
+        *self = grammar;
+        Ok(())
+    }
+}
+
+// the file format: header lines, then the rules of every nonterminal,
+// the start symbol first
+impl fmt::Display for ContextFreeGrammar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut nonterminals: Vec<&String> = self.nonterminals.iter().collect();
+        nonterminals.sort();
+        let mut terminals: Vec<&String> = self.terminals.iter().collect();
+        terminals.sort();
+        writeln!(f, "{}", nonterminals.iter().map(|symbol| symbol.as_str()).collect::<Vec<_>>().join(" "))?;
+        writeln!(f, "{}", terminals.iter().map(|symbol| symbol.as_str()).collect::<Vec<_>>().join(" "))?;
+        writeln!(f, "{}", self.start_symbol)?;
+
+        // heads in order of their first rule, start symbol first
+        let mut heads: Vec<&String> = vec![&self.start_symbol];
+        for production in &self.productions {
+            if !heads.contains(&&production.head) {
+                heads.push(&production.head);
+            }
+        }
+
+        for head in heads {
+            let bodies: Vec<String> = self.productions_of(head).map(Production::body_text).collect();
+            if !bodies.is_empty() {
+                writeln!(f, "{} -> {}", head, bodies.join(" | "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Production {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} -> {}", self.head, self.body_text())
+    }
+}
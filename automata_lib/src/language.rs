@@ -0,0 +1,335 @@
+/*
+ * decision procedures on the accepted language
+ * emptiness, finiteness, universality, size
+ * shortlex enumeration of the accepted words
+ * counting words by length, uniform sampling
+ */
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use num_bigint::{BigUint, RandBigInt};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::deterministic::DeterministicAutomaton;
+use crate::nondeterministic::NonDeterministicAutomaton;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LanguageSize {
+    Finite(BigUint),
+    Infinite,
+}
+
+#[doc = r"* accepted words in shortlex order (shorter first, then lexicographic)
+* bfs over the trimmed automaton with the symbols sorted, so every queued
+  word can still be extended to an accepted one"]
+pub struct ShortlexWords {
+    automaton: DeterministicAutomaton,
+    symbols: Vec<String>,
+    queue: VecDeque<(String, Vec<String>)>,
+    max_length: Option<usize>,
+}
+
+impl Iterator for ShortlexWords {
+    type Item = Vec<String>;
+
+    fn next(&mut self) -> Option<Vec<String>> {
+        while let Some((state, word)) = self.queue.pop_front() {
+            if self.max_length.is_none_or(|max_length| word.len() < max_length) {
+                for symbol in &self.symbols {
+                    if let Some(next) = self.automaton.transitions.get(&(state.clone(), symbol.clone())) {
+                        let mut next_word = word.clone();
+                        next_word.push(symbol.clone());
+                        self.queue.push_back((next.clone(), next_word));
+                    }
+                }
+            }
+
+            if self.automaton.terminal_states.contains(&state) {
+                return Some(word);
+            }
+        }
+        None
+    }
+}
+
+// kahn's algorithm, None if the graph has a cycle
+fn topological_order(states: &HashSet<String>, edges: &[(String, String)]) -> Option<Vec<String>> {
+    let mut in_degree: HashMap<&String, usize> = states.iter().map(|state| (state, 0)).collect();
+    let mut successors: HashMap<&String, Vec<&String>> = HashMap::new();
+    for (from, to) in edges {
+        // edges to or from unknown states are not part of the graph
+        if !states.contains(from) {
+            continue;
+        }
+        let Some(degree) = in_degree.get_mut(to) else {
+            continue;
+        };
+        *degree += 1;
+        successors.entry(from).or_default().push(to);
+    }
+
+    let mut queue: VecDeque<&String> = in_degree.iter()
+        .filter(|(_, degree)| **degree == 0)
+        .map(|(state, _)| *state)
+        .collect();
+    let mut order = Vec::new();
+    while let Some(state) = queue.pop_front() {
+        order.push(state.clone());
+        for next in successors.get(state).into_iter().flatten() {
+            let degree = in_degree.get_mut(*next).unwrap();
+            *degree -= 1;
+            if *degree == 0 {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    if order.len() == states.len() {
+        Some(order)
+    } else {
+        None
+    }
+}
+
+impl DeterministicAutomaton {
+    pub fn is_empty(&self) -> bool {
+        let mut trimmed = self.clone();
+        trimmed.trim();
+        trimmed.terminal_states.is_empty()
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.language_size() != LanguageSize::Infinite
+    }
+
+    // complete automata are universal if the complement has no terminal state left
+    pub fn is_universal(&self) -> bool {
+        self.complement().is_empty()
+    }
+
+    #[doc = r"* number of accepted words
+    * on the trimmed automaton every cycle can be used to pump, so the language
+      is infinite iff there is a cycle, otherwise the words are counted as paths
+      in reverse topological order"]
+    pub fn language_size(&self) -> LanguageSize {
+        let mut trimmed = self.clone();
+        trimmed.trim();
+
+        let edges: Vec<(String, String)> = trimmed.transitions.iter()
+            .map(|((from, _), to)| (from.clone(), to.clone()))
+            .collect();
+        let Some(order) = topological_order(&trimmed.states, &edges) else {
+            return LanguageSize::Infinite;
+        };
+
+        let mut counts: HashMap<&String, BigUint> = HashMap::new();
+        for state in order.iter().rev() {
+            let mut count = BigUint::from(u8::from(trimmed.terminal_states.contains(state)));
+            for symbol in &trimmed.alphabet {
+                if let Some(next) = trimmed.transitions.get(&(state.clone(), symbol.clone())) {
+                    count += &counts[next];
+                }
+            }
+            counts.insert(state, count);
+        }
+
+        let total = trimmed.start_states.iter().map(|start| counts[start].clone()).sum();
+        LanguageSize::Finite(total)
+    }
+}
+
+impl DeterministicAutomaton {
+    pub fn accepted_words(&self, max_length: Option<usize>) -> ShortlexWords {
+        let mut automaton = self.clone();
+        automaton.trim();
+
+        let mut symbols: Vec<String> = automaton.alphabet.iter().cloned().collect();
+        symbols.sort();
+        let queue = automaton.start_states.iter().min()
+            .map(|start| (start.clone(), Vec::new()))
+            .into_iter()
+            .collect();
+
+        ShortlexWords {
+            automaton,
+            symbols,
+            queue,
+            max_length,
+        }
+    }
+}
+
+// trimmed dfa with integer states for the dynamic programming
+struct CountingTable {
+    symbols: Vec<String>,
+    delta: Vec<Vec<Option<usize>>>,
+    terminal: Vec<bool>,
+    start: Option<usize>,
+}
+
+impl CountingTable {
+    fn new(dfa: &DeterministicAutomaton) -> Self {
+        let mut trimmed = dfa.clone();
+        trimmed.trim();
+
+        let mut names: Vec<&String> = trimmed.states.iter().collect();
+        names.sort();
+        let index_of: HashMap<&String, usize> = names.iter().enumerate().map(|(index, name)| (*name, index)).collect();
+        let mut symbols: Vec<String> = trimmed.alphabet.iter().cloned().collect();
+        symbols.sort();
+
+        CountingTable {
+            delta: names.iter()
+                .map(|name| symbols.iter()
+                    .map(|symbol| trimmed.transitions.get(&(name.to_string(), symbol.clone())).map(|to| index_of[to]))
+                    .collect())
+                .collect(),
+            terminal: names.iter().map(|name| trimmed.terminal_states.contains(*name)).collect(),
+            start: trimmed.start_states.iter().min().map(|start| index_of[start]),
+            symbols,
+        }
+    }
+
+    // counts[k][q]: number of words of length k accepted from q
+    fn backward_counts(&self, max_length: usize) -> Vec<Vec<BigUint>> {
+        let mut counts = vec![self.terminal.iter().map(|&terminal| BigUint::from(u8::from(terminal))).collect::<Vec<_>>()];
+        for length in 1..=max_length {
+            let previous = &counts[length - 1];
+            let row = self.delta.iter()
+                .map(|row| row.iter().flatten().map(|&next| &previous[next]).sum())
+                .collect();
+            counts.push(row);
+        }
+        counts
+    }
+}
+
+impl DeterministicAutomaton {
+    #[doc = r"* number of accepted words of every length from 0 to max_length
+    * forward dynamic programming: how many words of length k lead to each state"]
+    pub fn count_words_by_length(&self, max_length: usize) -> Vec<BigUint> {
+        let table = CountingTable::new(self);
+        let Some(start) = table.start else {
+            return vec![BigUint::from(0u8); max_length + 1];
+        };
+
+        let mut reaching: Vec<BigUint> = vec![BigUint::from(0u8); table.delta.len()];
+        reaching[start] = BigUint::from(1u8);
+        let mut result = Vec::with_capacity(max_length + 1);
+        for length in 0..=max_length {
+            result.push(reaching.iter().zip(&table.terminal).filter(|(_, &terminal)| terminal).map(|(count, _)| count).sum());
+            if length < max_length {
+                let mut next_reaching = vec![BigUint::from(0u8); table.delta.len()];
+                for (state, row) in table.delta.iter().enumerate() {
+                    for &next in row.iter().flatten() {
+                        next_reaching[next] += &reaching[state];
+                    }
+                }
+                reaching = next_reaching;
+            }
+        }
+        result
+    }
+
+    pub fn count_words_of_length(&self, length: usize) -> BigUint {
+        self.count_words_by_length(length).pop().unwrap()
+    }
+
+    #[doc = r"* uniformly random accepted word of the given length, None if there is none
+    * every symbol is chosen with probability proportional to the number of
+      accepted completions after it"]
+    pub fn random_word<R: Rng + ?Sized>(&self, length: usize, rng: &mut R) -> Option<Vec<String>> {
+        let table = CountingTable::new(self);
+        let counts = table.backward_counts(length);
+        let mut state = table.start?;
+        if counts[length][state] == BigUint::from(0u8) {
+            return None;
+        }
+
+        let mut word = Vec::with_capacity(length);
+        for remaining in (1..=length).rev() {
+            let mut choice = rng.gen_biguint_below(&counts[remaining][state]);
+            for (symbol, next) in table.delta[state].iter().enumerate() {
+                let Some(next) = *next else {
+                    continue;
+                };
+                let completions = &counts[remaining - 1][next];
+                if choice < *completions {
+                    word.push(table.symbols[symbol].clone());
+                    state = next;
+                    break;
+                }
+                choice -= completions;
+            }
+        }
+        Some(word)
+    }
+
+    // reproducible samples with a seeded rng
+    pub fn random_words(&self, length: usize, count: usize, seed: u64) -> Vec<Vec<String>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..count).map_while(|_| self.random_word(length, &mut rng)).collect()
+    }
+}
+
+impl NonDeterministicAutomaton {
+    pub fn accepted_words(&self, max_length: Option<usize>) -> ShortlexWords {
+        self.to_deterministic().accepted_words(max_length)
+    }
+
+    // no terminal state is reachable from the start states
+    pub fn is_empty(&self) -> bool {
+        let reachable = self.reachable_from(&self.start_states, false);
+        !reachable.iter().any(|state| self.terminal_states.contains(state))
+    }
+
+    #[doc = r"* cycle detection on the useful part of the epsilon free automaton
+    * (eps cycles would not add new words)"]
+    pub fn is_finite(&self) -> bool {
+        let mut eps_free = self.clone();
+        eps_free.remove_epsilon_transitions();
+
+        let reachable = eps_free.reachable_from(&eps_free.start_states, false);
+        let productive = eps_free.reachable_from(&eps_free.terminal_states, true);
+        let useful: HashSet<String> = reachable.intersection(&productive).cloned().collect();
+
+        let mut edges = Vec::new();
+        for ((from, _), to_states) in &eps_free.transitions {
+            for to in to_states {
+                if useful.contains(from) && useful.contains(to) {
+                    edges.push((from.clone(), to.clone()));
+                }
+            }
+        }
+        topological_order(&useful, &edges).is_some()
+    }
+
+    pub fn is_universal(&self) -> bool {
+        self.to_deterministic().is_universal()
+    }
+
+    // words have to be counted on the deterministic automaton, an nfa can
+    // accept the same word on several paths
+    pub fn language_size(&self) -> LanguageSize {
+        self.to_deterministic().language_size()
+    }
+
+    // states reachable from the given ones over any transition (backwards if reversed)
+    fn reachable_from(&self, states: &HashSet<String>, reversed: bool) -> HashSet<String> {
+        let mut visited = states.clone();
+        let mut queue: VecDeque<String> = states.iter().cloned().collect();
+
+        while let Some(current) = queue.pop_front() {
+            for ((from, _), to_states) in &self.transitions {
+                for to in to_states {
+                    let (source, target) = if reversed { (to, from) } else { (from, to) };
+                    if *source == current && visited.insert(target.clone()) {
+                        queue.push_back(target.clone());
+                    }
+                }
+            }
+        }
+        visited
+    }
+}
@@ -3,9 +3,19 @@ pub mod pushdown;
 pub mod traits;
 pub mod utils;
 pub mod nondeterministic;
+pub mod minimization;
+pub mod language;
+pub mod regex;
+pub mod error;
+pub mod grammar;
 
 pub use deterministic::*;
 pub use pushdown::*;
 pub use traits::*;
 pub use utils::*;
-pub use nondeterministic::*;
\ No newline at end of file
+pub use nondeterministic::*;
+pub use minimization::*;
+pub use language::*;
+pub use regex::*;
+pub use error::*;
+pub use grammar::*;
\ No newline at end of file
@@ -0,0 +1,427 @@
+/*
+ * minimization algorithms for dfa
+ * hopcroft, moore, brzozowski, all of them drop the unreachable and dead states
+ * minimization report
+ */
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use crate::deterministic::DeterministicAutomaton;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MinimizationAlgorithm {
+    // the set based partition refinement of `minimize`
+    Partition,
+    Hopcroft,
+    Moore,
+    Brzozowski,
+}
+
+// states of the original automaton that became one state
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergedBlock {
+    pub representative: String,
+    pub states: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MinimizationReport {
+    pub removed_unreachable: Vec<String>,
+    pub removed_dead: Vec<String>,
+    pub merged_blocks: Vec<MergedBlock>,
+    pub automaton: DeterministicAutomaton,
+}
+
+impl MinimizationReport {
+    pub fn is_minimal(&self) -> bool {
+        self.removed_unreachable.is_empty() && self.removed_dead.is_empty() && self.merged_blocks.is_empty()
+    }
+}
+
+// partition refinement structure: every block is a range of `elements`,
+// states marked during a split are swapped to the front of their block
+struct Partition {
+    elements: Vec<usize>,
+    location: Vec<usize>,
+    block_of: Vec<usize>,
+    blocks: Vec<(usize, usize)>,
+    marked: Vec<usize>,
+}
+
+impl Partition {
+    fn new(size: usize) -> Self {
+        Partition {
+            elements: (0..size).collect(),
+            location: (0..size).collect(),
+            block_of: vec![0; size],
+            blocks: vec![(0, size)],
+            marked: vec![0],
+        }
+    }
+
+    fn block_size(&self, block: usize) -> usize {
+        self.blocks[block].1 - self.blocks[block].0
+    }
+
+    fn states(&self, block: usize) -> &[usize] {
+        let (start, end) = self.blocks[block];
+        &self.elements[start..end]
+    }
+
+    // returns true if this is the first marked state of its block
+    fn mark(&mut self, state: usize) -> bool {
+        let block = self.block_of[state];
+        let (start, _) = self.blocks[block];
+        let target = start + self.marked[block];
+        let position = self.location[state];
+        if position < target {
+            return false;
+        }
+
+        let other = self.elements[target];
+        self.elements.swap(position, target);
+        self.location[other] = position;
+        self.location[state] = target;
+        self.marked[block] += 1;
+        self.marked[block] == 1
+    }
+
+    // splits off the marked part as a new block, if it is a proper subset
+    fn split(&mut self, block: usize) -> Option<usize> {
+        let marked = self.marked[block];
+        self.marked[block] = 0;
+        if marked == self.block_size(block) {
+            return None;
+        }
+
+        let (start, end) = self.blocks[block];
+        let new_block = self.blocks.len();
+        self.blocks[block] = (start + marked, end);
+        self.blocks.push((start, start + marked));
+        self.marked.push(0);
+        for &state in &self.elements[start..start + marked] {
+            self.block_of[state] = new_block;
+        }
+        Some(new_block)
+    }
+}
+
+// the reachable part of a dfa with integer states, completed by a virtual
+// sink (index `sink`) when transitions are missing, `dead` is a state that
+// cannot reach a terminal state, if there is one
+struct IndexedAutomaton {
+    names: Vec<String>,
+    symbols: Vec<String>,
+    delta: Vec<Vec<Option<usize>>>,
+    terminal: Vec<bool>,
+    sink: usize,
+    size: usize,
+    dead: Option<usize>,
+}
+
+impl IndexedAutomaton {
+    fn new(dfa: &DeterministicAutomaton, start: &String) -> Self {
+        let mut symbols: Vec<String> = dfa.alphabet.iter().cloned().collect();
+        symbols.sort();
+
+        let mut names: Vec<String> = vec![start.clone()];
+        let mut index_of: HashMap<&String, usize> = HashMap::from([(start, 0)]);
+        let mut delta: Vec<Vec<Option<usize>>> = Vec::new();
+        let mut queue = VecDeque::from([0]);
+        while let Some(state) = queue.pop_front() {
+            let mut row = Vec::with_capacity(symbols.len());
+            for symbol in &symbols {
+                let next = dfa.transitions.get(&(names[state].clone(), symbol.clone())).map(|to| {
+                    *index_of.entry(to).or_insert_with(|| {
+                        names.push(to.clone());
+                        queue.push_back(names.len() - 1);
+                        names.len() - 1
+                    })
+                });
+                row.push(next);
+            }
+            delta.push(row);
+        }
+
+        let sink = names.len();
+        let has_sink = delta.iter().flatten().any(Option::is_none);
+        let mut terminal: Vec<bool> = names.iter().map(|name| dfa.terminal_states.contains(name)).collect();
+        if has_sink {
+            terminal.push(false);
+        }
+
+        let mut indexed = IndexedAutomaton {
+            size: if has_sink { sink + 1 } else { sink },
+            names,
+            symbols,
+            delta,
+            terminal,
+            sink,
+            dead: None,
+        };
+        indexed.dead = indexed.find_dead_state();
+        indexed
+    }
+
+    // backwards search from the terminal states over the inverse tables
+    fn find_dead_state(&self) -> Option<usize> {
+        let mut previous: Vec<Vec<usize>> = vec![Vec::new(); self.size];
+        for state in 0..self.size {
+            for symbol in 0..self.symbols.len() {
+                previous[self.next(state, symbol)].push(state);
+            }
+        }
+
+        let mut productive = self.terminal.clone();
+        let mut queue: VecDeque<usize> = (0..self.size).filter(|&state| productive[state]).collect();
+        while let Some(state) = queue.pop_front() {
+            for &before in &previous[state] {
+                if !productive[before] {
+                    productive[before] = true;
+                    queue.push_back(before);
+                }
+            }
+        }
+        productive.iter().position(|&productive| !productive)
+    }
+
+    fn next(&self, state: usize, symbol: usize) -> usize {
+        if state == self.sink {
+            self.sink
+        } else {
+            self.delta[state][symbol].unwrap_or(self.sink)
+        }
+    }
+
+    #[doc = r"* builds the quotient automaton for the given block of every state
+    * blocks are named after their smallest real state
+    * the dead states all fall into one block, transitions into it are left
+      out and its states are dropped, except the start state"]
+    fn quotient(&self, original: &DeterministicAutomaton, block_of: &[usize]) -> (DeterministicAutomaton, HashMap<String, String>) {
+        let mut minimized = DeterministicAutomaton::new();
+        minimized.alphabet = original.alphabet.clone();
+        let dead_block = self.dead.map(|dead| block_of[dead]);
+
+        let mut block_names: HashMap<usize, String> = HashMap::new();
+        for (state, state_name) in self.names.iter().enumerate() {
+            let name = block_names.entry(block_of[state]).or_insert_with(|| state_name.clone());
+            if state_name < name {
+                *name = state_name.clone();
+            }
+        }
+
+        let mut mapping = HashMap::new();
+        for (state, state_name) in self.names.iter().enumerate() {
+            if state != 0 && Some(block_of[state]) == dead_block {
+                continue;
+            }
+            let name = block_names[&block_of[state]].clone();
+            minimized.states.insert(name.clone());
+            if self.terminal[state] {
+                minimized.terminal_states.insert(name.clone());
+            }
+            for (symbol, symbol_name) in self.symbols.iter().enumerate() {
+                let next_block = block_of[self.next(state, symbol)];
+                if Some(next_block) != dead_block {
+                    minimized.transitions.insert((name.clone(), symbol_name.clone()), block_names[&next_block].clone());
+                }
+            }
+            mapping.insert(state_name.clone(), name);
+        }
+        minimized.start_states.insert(mapping[&self.names[0]].clone());
+
+        (minimized, mapping)
+    }
+}
+
+impl DeterministicAutomaton {
+    #[doc = r"* the states every minimization starts from: reachable and productive
+    * the start state is kept even if the language is empty, so the common
+      result of the algorithms is the minimal automaton without dead states,
+      a single non terminal start state for the empty language"]
+    pub(crate) fn useful_part(&self) -> DeterministicAutomaton {
+        let mut useful = self.clone();
+        let start = useful.start_states.iter().min().cloned();
+        useful.trim();
+        if let (Some(start), true) = (start, useful.start_states.is_empty()) {
+            useful.states.insert(start.clone());
+            useful.start_states.insert(start);
+        }
+        useful
+    }
+
+    #[doc = r"* removes the unreachable and dead states, then merges the equivalent
+    * ones with hopcroft (missing transitions are completed internally)
+    * every merged block is represented by its smallest state name"]
+    pub fn minimization_report(&self) -> MinimizationReport {
+        let mut reachable = self.clone();
+        let removed_unreachable = reachable.remove_unreachable_states();
+        let useful = reachable.useful_part();
+        let mut removed_dead: Vec<String> = reachable.states.difference(&useful.states).cloned().collect();
+        removed_dead.sort();
+
+        let (automaton, mapping) = useful.minimize_hopcroft();
+
+        let mut blocks: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (state, representative) in mapping {
+            blocks.entry(representative).or_default().push(state);
+        }
+        let merged_blocks = blocks.into_iter()
+            .filter(|(_, states)| states.len() > 1)
+            .map(|(representative, mut states)| {
+                states.sort();
+                MergedBlock { representative, states }
+            })
+            .collect();
+
+        MinimizationReport {
+            removed_unreachable,
+            removed_dead,
+            merged_blocks,
+            automaton,
+        }
+    }
+
+    pub fn minimize_with(&self, algorithm: MinimizationAlgorithm) -> DeterministicAutomaton {
+        match algorithm {
+            MinimizationAlgorithm::Partition => self.minimize(),
+            MinimizationAlgorithm::Hopcroft => self.minimize_hopcroft().0,
+            MinimizationAlgorithm::Moore => self.minimize_moore().0,
+            MinimizationAlgorithm::Brzozowski => self.minimize_brzozowski(),
+        }
+    }
+
+    #[doc = r"* hopcroft minimization in O(n·k·log n)
+    * works on the reachable part with integer states and inverse transition
+      tables, missing transitions go to a virtual sink, the dead states end
+      up in one block that is left out
+    * returns the minimal automaton and the block (new state name) of every
+      useful old state, blocks are named after their smallest state"]
+    pub fn minimize_hopcroft(&self) -> (DeterministicAutomaton, HashMap<String, String>) {
+        let Some(start) = self.start_states.iter().min() else {
+            let mut minimized = DeterministicAutomaton::new();
+            minimized.alphabet = self.alphabet.clone();
+            return (minimized, HashMap::new());
+        };
+
+        let indexed = IndexedAutomaton::new(self, start);
+        let (size, symbol_count) = (indexed.size, indexed.symbols.len());
+
+        let mut inverse: Vec<Vec<Vec<usize>>> = vec![vec![Vec::new(); size]; symbol_count];
+        for state in 0..size {
+            for (symbol, symbol_inverse) in inverse.iter_mut().enumerate() {
+                symbol_inverse[indexed.next(state, symbol)].push(state);
+            }
+        }
+
+        // initial partition: terminal and non terminal states
+        let mut partition = Partition::new(size);
+        for state in 0..size {
+            if indexed.terminal[state] {
+                partition.mark(state);
+            }
+        }
+        let mut worklist: VecDeque<(usize, usize)> = VecDeque::new();
+        let mut in_worklist: Vec<Vec<bool>> = vec![vec![false; symbol_count]];
+        if let Some(terminal_block) = partition.split(0) {
+            let smaller = if partition.block_size(terminal_block) <= partition.block_size(0) { terminal_block } else { 0 };
+            in_worklist.push(vec![false; symbol_count]);
+            for (symbol, waiting) in in_worklist[smaller].iter_mut().enumerate() {
+                worklist.push_back((smaller, symbol));
+                *waiting = true;
+            }
+        }
+
+        while let Some((splitter, symbol)) = worklist.pop_front() {
+            in_worklist[splitter][symbol] = false;
+
+            let mut touched = Vec::new();
+            let splitter_states: Vec<usize> = partition.states(splitter).to_vec();
+            for state in splitter_states {
+                for &previous in &inverse[symbol][state] {
+                    if partition.mark(previous) {
+                        touched.push(partition.block_of[previous]);
+                    }
+                }
+            }
+
+            for block in touched {
+                let Some(new_block) = partition.split(block) else {
+                    continue;
+                };
+                in_worklist.push(vec![false; symbol_count]);
+                let block_waiting = in_worklist[block].clone();
+                for (other_symbol, waiting) in block_waiting.into_iter().enumerate() {
+                    // if the old block is waiting already both halves are needed,
+                    // otherwise the smaller half is enough
+                    let added = if waiting
+                        || partition.block_size(new_block) <= partition.block_size(block)
+                    {
+                        new_block
+                    } else {
+                        block
+                    };
+                    if !in_worklist[added][other_symbol] {
+                        in_worklist[added][other_symbol] = true;
+                        worklist.push_back((added, other_symbol));
+                    }
+                }
+            }
+        }
+
+        indexed.quotient(self, &partition.block_of)
+    }
+
+    #[doc = r"* moore minimization
+    * the states are split by (own class, class after every symbol) signatures
+      until the number of classes stops growing"]
+    pub fn minimize_moore(&self) -> (DeterministicAutomaton, HashMap<String, String>) {
+        let Some(start) = self.start_states.iter().min() else {
+            let mut minimized = DeterministicAutomaton::new();
+            minimized.alphabet = self.alphabet.clone();
+            return (minimized, HashMap::new());
+        };
+
+        let indexed = IndexedAutomaton::new(self, start);
+        let mut class_of: Vec<usize> = indexed.terminal.iter().map(|&terminal| usize::from(terminal)).collect();
+        let mut class_count = 0;
+
+        loop {
+            let mut signatures: HashMap<Vec<usize>, usize> = HashMap::new();
+            let new_class_of: Vec<usize> = (0..indexed.size)
+                .map(|state| {
+                    let mut signature = vec![class_of[state]];
+                    signature.extend((0..indexed.symbols.len()).map(|symbol| class_of[indexed.next(state, symbol)]));
+                    let next_class = signatures.len();
+                    *signatures.entry(signature).or_insert(next_class)
+                })
+                .collect();
+
+            class_of = new_class_of;
+            if signatures.len() == class_count {
+                break;
+            }
+            class_count = signatures.len();
+        }
+
+        indexed.quotient(self, &class_of)
+    }
+
+    #[doc = r"* brzozowski minimization: det(rev(det(rev(A))))
+    * the subset construction leaves out the empty set, so there is no dead state
+    * the result is named by the indices of the last subset construction"]
+    pub fn minimize_brzozowski(&self) -> DeterministicAutomaton {
+        if self.start_states.is_empty() {
+            let mut minimized = DeterministicAutomaton::new();
+            minimized.alphabet = self.alphabet.clone();
+            return minimized;
+        }
+
+        self.to_nondeterministic()
+            .reverse()
+            .to_deterministic()
+            .to_nondeterministic()
+            .reverse()
+            .to_deterministic()
+    }
+}
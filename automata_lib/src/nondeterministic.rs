@@ -1,4 +1,4 @@
-/**
+/*
  * Gyorgy Matyas
  * gmim2236
  * 1.B.04
@@ -9,12 +9,29 @@
 
 use std::collections::{HashMap, HashSet, VecDeque, BTreeSet};
 use std::fmt;
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
 
 use crate::traits::Automaton;
+use crate::error::{AutomatonError, SourceLines};
+use crate::utils::{dot_id, escape_dot_label, split_word};
 use crate::deterministic::DeterministicAutomaton;
+use crate::regex::{eliminate_states, Regex};
+
+// how the subset construction names the new states
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StateNaming {
+    // 0, 1, 2, ... in discovery order
+    Index,
+    // the nfa states of the subset, e.g. {1,3}
+    Subset,
+}
+
+// node of the antichain based inclusion check
+#[derive(Clone)]
+struct InclusionNode {
+    state: String,
+    macrostate: BTreeSet<String>,
+    parent: Option<(usize, String)>,
+}
 
 #[derive(Clone, Debug)]
 pub struct NonDeterministicAutomaton {
@@ -25,6 +42,12 @@ pub struct NonDeterministicAutomaton {
     pub transitions: HashMap<(String, String), HashSet<String>>,
 }
 
+impl Default for NonDeterministicAutomaton {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NonDeterministicAutomaton {
     pub fn new() -> Self {
         NonDeterministicAutomaton {
@@ -39,17 +62,52 @@ impl NonDeterministicAutomaton {
     pub fn add_transition(&mut self, from: String, symbol: String, to: String) {
         self.transitions
             .entry((from, symbol))
-            .or_insert_with(HashSet::new)
+            .or_default()
             .insert(to);
     }
 
+    // states reachable through eps transitions only, including the given ones
+    pub fn epsilon_closure(&self, states: &HashSet<String>) -> HashSet<String> {
+        let mut closure = states.clone();
+        let mut stack: Vec<String> = states.iter().cloned().collect();
+
+        while let Some(state) = stack.pop() {
+            if let Some(next_states) = self.transitions.get(&(state, "eps".to_string())) {
+                for next in next_states {
+                    if closure.insert(next.clone()) {
+                        stack.push(next.clone());
+                    }
+                }
+            }
+        }
+        closure
+    }
+
+    // closure of the states reachable with one symbol step
+    fn step(&self, states: &HashSet<String>, symbol: &str) -> HashSet<String> {
+        let mut next_states = HashSet::new();
+        for state in states {
+            if let Some(next) = self.transitions.get(&(state.clone(), symbol.to_string())) {
+                next_states.extend(next.iter().cloned());
+            }
+        }
+        self.epsilon_closure(&next_states)
+    }
+
+    pub fn to_deterministic(&self) -> DeterministicAutomaton {
+        self.to_deterministic_with(StateNaming::Index)
+    }
+
     #[doc = r"* Gyorgy Matyas
     * gmim2236
     * 1.B.04
     * pda"]
-    pub fn to_deterministic(&self) -> DeterministicAutomaton{
+    pub fn to_deterministic_with(&self, naming: StateNaming) -> DeterministicAutomaton {
         let mut dfa = DeterministicAutomaton::new();
-        let transition_map = self.transitions.clone();
+
+        // eps is not a real symbol, it is handled by the closures
+        let mut symbols: Vec<&String> = self.alphabet.iter().filter(|symbol| *symbol != "eps").collect();
+        symbols.sort();
 
         // we need btreeset for hashing
         let mut dfa_state_index: HashMap<BTreeSet<String>, i32> = HashMap::new();
@@ -58,22 +116,17 @@ impl NonDeterministicAutomaton {
 
         let mut state_counter = 0;
         
-        let dfa_start_state: BTreeSet<String> = self.start_states.iter().cloned().collect();
+        let dfa_start_state: BTreeSet<String> = self.epsilon_closure(&self.start_states).into_iter().collect();
         dfa_states.push_back(dfa_start_state.clone());
         dfa_state_index.insert(dfa_start_state.clone(), state_counter);
         
         state_counter += 1;
         while let Some(current_dfa_state) = dfa_states.pop_front() {
             let current_state_id = dfa_state_index[&current_dfa_state];
+            let current_set: HashSet<String> = current_dfa_state.iter().cloned().collect();
 
-            for symbol in &self.alphabet {
-                let mut next_state_set = BTreeSet::new();
-
-                for state in &current_dfa_state {
-                    if let Some(next_states) = transition_map.get(&(state.clone(), symbol.clone())) {
-                        next_state_set.extend(next_states.clone());
-                    }
-                }
+            for symbol in &symbols {
+                let next_state_set: BTreeSet<String> = self.step(&current_set, symbol).into_iter().collect();
 
                 if !next_state_set.is_empty() {
                     // check if the new state set is indexed
@@ -87,79 +140,181 @@ impl NonDeterministicAutomaton {
                     };
                     
                     // add transition
-                    dfa_transition_map.insert((current_state_id, symbol.clone()), next_state_id);
+                    dfa_transition_map.insert((current_state_id, symbol.to_string()), next_state_id);
                 }
             }
         }
 
-        // collect dfa start state (should be only one)
+        let mut state_names: HashMap<i32, String> = HashMap::new();
         for (state_set, index) in &dfa_state_index {
+            let name = match naming {
+                StateNaming::Index => index.to_string(),
+                StateNaming::Subset => format!("{{{}}}", state_set.iter().cloned().collect::<Vec<String>>().join(",")),
+            };
             if state_set.iter().any(|s| self.terminal_states.contains(s)) {
-                dfa.terminal_states.insert(index.to_string());
+                dfa.terminal_states.insert(name.clone());
             }
+            dfa.states.insert(name.clone());
+            state_names.insert(*index, name);
         }
 
         // set up dfa
-        dfa.start_states.insert(dfa_state_index[&dfa_start_state].to_string());
+        dfa.start_states.insert(state_names[&dfa_state_index[&dfa_start_state]].clone());
         dfa.transitions = dfa_transition_map.into_iter()
-            .map(|((from, symbol), to)| ((from.to_string(), symbol), vec![to.to_string()].into_iter().collect()))
+            .map(|((from, symbol), to)| ((state_names[&from].clone(), symbol), state_names[&to].clone()))
             .collect();
-        
-        for ((source, _), target) in &dfa.transitions {
-            dfa.states.insert(source.clone());
-            dfa.states.insert(target.clone());
-        }
-        dfa.alphabet = self.alphabet.clone();
+        dfa.alphabet = symbols.into_iter().cloned().collect();
 
         dfa
     }
 
-    #[doc = r"* Gyorgy Matyas
-    * gmim2236
-    * 1.A.04"]
-    pub fn accepts(&self, word: &str) -> bool {
-        fn accepts_helper(
-            current_states: &HashSet<String>,
-            terminal_states: &HashSet<String>,
-            transitions: &HashMap<(String, String), HashSet<String>>,
-            word: &str,
-            index: usize,
-            iteration: usize,
-        ) -> bool {
-            const MAX_ITERATION: usize = 1000;
-            if index == word.len() {
-                return current_states.iter().any(|state| terminal_states.contains(state));
+    #[doc = r"* L(self) ⊆ L(other) check with antichains
+    * explores (state of self, macrostate of other) pairs in bfs order, a pair is
+      dropped when one with the same state and a smaller macrostate was already seen
+    * on failure returns the shortest word in L(self) \ L(other)"]
+    pub fn check_inclusion(&self, other: &NonDeterministicAutomaton) -> Result<(), Vec<String>> {
+        let mut symbols: Vec<&String> = self.alphabet.iter().filter(|symbol| *symbol != "eps").collect();
+        symbols.sort();
+
+        let mut antichain: HashMap<String, Vec<BTreeSet<String>>> = HashMap::new();
+        let mut nodes: Vec<InclusionNode> = Vec::new();
+
+        let mut add_node = |nodes: &mut Vec<InclusionNode>, state: String, macrostate: BTreeSet<String>, parent: Option<(usize, String)>| {
+            let seen = antichain.entry(state.clone()).or_default();
+            if seen.iter().any(|smaller| smaller.is_subset(&macrostate)) {
+                return;
             }
+            seen.retain(|bigger| !macrostate.is_subset(bigger));
+            seen.push(macrostate.clone());
+            nodes.push(InclusionNode { state, macrostate, parent });
+        };
+
+        let other_start: BTreeSet<String> = other.epsilon_closure(&other.start_states).into_iter().collect();
+        let mut starts: Vec<String> = self.epsilon_closure(&self.start_states).into_iter().collect();
+        starts.sort();
+        for state in starts {
+            add_node(&mut nodes, state, other_start.clone(), None);
+        }
 
-            if iteration == MAX_ITERATION {
-                return false;
-            }
+        let mut index = 0;
+        while index < nodes.len() {
+            let InclusionNode { state, macrostate, .. } = nodes[index].clone();
 
-            let symbol = &word[index..index + 1];
-            let mut next_states = HashSet::new();
-            let mut step = 1;
+            if self.terminal_states.contains(&state) && macrostate.iter().all(|s| !other.terminal_states.contains(s)) {
+                let mut word = Vec::new();
+                let mut current = index;
+                while let Some((parent, symbol)) = &nodes[current].parent {
+                    word.push(symbol.clone());
+                    current = *parent;
+                }
+                word.reverse();
+                return Err(word);
+            }
 
-            for state in current_states {
-                if let Some(next) = transitions.get(&(state.clone(), symbol.to_string())) {
-                    next_states.extend(next.clone());
+            let macrostate_set: HashSet<String> = macrostate.iter().cloned().collect();
+            for symbol in &symbols {
+                let mut next_states: Vec<String> = self.step(&HashSet::from([state.clone()]), symbol).into_iter().collect();
+                if next_states.is_empty() {
+                    continue;
                 }
-                if let Some(epsilon_next) = transitions.get(&(state.clone(), "eps".to_string())) {
-                    next_states.extend(epsilon_next.clone());
-                    step = 0;
+                next_states.sort();
+                let next_macrostate: BTreeSet<String> = other.step(&macrostate_set, symbol).into_iter().collect();
+                for next in next_states {
+                    add_node(&mut nodes, next, next_macrostate.clone(), Some((index, symbol.to_string())));
                 }
             }
+            index += 1;
+        }
 
-            if next_states.is_empty() {
-                return false;
+        Ok(())
+    }
+
+    pub fn is_subset_of(&self, other: &NonDeterministicAutomaton) -> bool {
+        self.check_inclusion(other).is_ok()
+    }
+
+    #[doc = r"* replaces eps transitions with direct ones through the eps closures
+    * q -a-> r for every p in closure(q) with p -a-> r
+    * q becomes terminal if its closure contains a terminal state"]
+    pub fn remove_epsilon_transitions(&mut self) {
+        let mut new_transitions: HashMap<(String, String), HashSet<String>> = HashMap::new();
+        let mut new_terminal_states = self.terminal_states.clone();
+
+        for state in &self.states {
+            let closure = self.epsilon_closure(&HashSet::from([state.clone()]));
+            if closure.iter().any(|s| self.terminal_states.contains(s)) {
+                new_terminal_states.insert(state.clone());
             }
 
-            accepts_helper(&next_states, terminal_states, transitions, word, index + step, iteration + 1)
+            for ((from, symbol), to_states) in &self.transitions {
+                if symbol != "eps" && closure.contains(from) {
+                    new_transitions
+                        .entry((state.clone(), symbol.clone()))
+                        .or_default()
+                        .extend(to_states.iter().cloned());
+                }
+            }
+        }
+
+        self.transitions = new_transitions;
+        self.terminal_states = new_terminal_states;
+        self.alphabet.remove("eps");
+    }
+
+    // complementing needs a complete deterministic automaton
+    pub fn complement(&self) -> DeterministicAutomaton {
+        self.to_deterministic().complement()
+    }
+
+    pub fn complement_over(&self, alphabet: &HashSet<String>) -> DeterministicAutomaton {
+        self.to_deterministic().complement_over(alphabet)
+    }
+
+    #[doc = r"* Gyorgy Matyas
+    * gmim2236
+    * 1.A.04"]
+    pub fn accepts(&self, word: &str) -> bool {
+        if word.contains(char::is_whitespace) {
+            return self.accepts_symbols(&split_word(word, &self.alphabet));
+        }
+
+        // the alphabet need not be prefix free ({a, ab, bc} reads abc as a bc),
+        // so every symbol matching at a position is tried, reached[i] is the
+        // eps closed set of states after reading the first i bytes
+        let symbols: HashSet<&String> = self.alphabet.iter()
+            .chain(self.transitions.keys().map(|(_, symbol)| symbol))
+            .filter(|symbol| !symbol.is_empty() && symbol.as_str() != "eps")
+            .collect();
+        let mut reached = vec![HashSet::new(); word.len() + 1];
+        reached[0] = self.epsilon_closure(&self.start_states);
+
+        for position in 0..word.len() {
+            if reached[position].is_empty() {
+                continue;
+            }
+            for symbol in &symbols {
+                if word[position..].starts_with(symbol.as_str()) {
+                    let next_states = self.step(&reached[position], symbol);
+                    reached[position + symbol.len()].extend(next_states);
+                }
+            }
         }
 
-        let start_set = &self.start_states;
-        let terminal_set = &self.terminal_states;
+        reached[word.len()].iter().any(|state| self.terminal_states.contains(state))
+    }
 
-        accepts_helper(start_set, terminal_set, &self.transitions, word, 0, 0)
+    // set of states simulation, the current set is always eps closed
+    pub fn accepts_symbols(&self, word: &[String]) -> bool {
+        let mut current_states = self.epsilon_closure(&self.start_states);
+
+        for symbol in word {
+            if current_states.is_empty() {
+                return false;
+            }
+            current_states = self.step(&current_states, symbol);
+        }
+
+        current_states.iter().any(|state| self.terminal_states.contains(state))
     }
 
     pub fn try_all_words(&self, words_list: Vec<String>) {
@@ -172,6 +327,127 @@ impl NonDeterministicAutomaton {
         }
     }
 
+    // swaps start and terminal states and flips every transition
+    pub fn reverse(&self) -> NonDeterministicAutomaton {
+        let mut reversed = NonDeterministicAutomaton::new();
+        reversed.states = self.states.clone();
+        reversed.alphabet = self.alphabet.clone();
+        reversed.start_states = self.terminal_states.clone();
+        reversed.terminal_states = self.start_states.clone();
+        for ((from, symbol), to_states) in &self.transitions {
+            for to in to_states {
+                reversed.add_transition(to.clone(), symbol.clone(), from.clone());
+            }
+        }
+        reversed
+    }
+
+    // copy with every state renamed to prefix_state
+    fn with_prefix(&self, prefix: &str) -> NonDeterministicAutomaton {
+        let rename = |state: &String| format!("{}_{}", prefix, state);
+        let mut renamed = NonDeterministicAutomaton::new();
+        renamed.states = self.states.iter().map(rename).collect();
+        renamed.alphabet = self.alphabet.clone();
+        renamed.start_states = self.start_states.iter().map(rename).collect();
+        renamed.terminal_states = self.terminal_states.iter().map(rename).collect();
+        renamed.transitions = self.transitions.iter()
+            .map(|((from, symbol), to_states)| ((rename(from), symbol.clone()), to_states.iter().map(rename).collect()))
+            .collect();
+        renamed
+    }
+
+    // a state name not used yet, also not as a transition target: base, base1, base2, ...
+    fn fresh_state(&self, base: &str) -> String {
+        let used = |name: &String| {
+            self.states.contains(name)
+                || self.transitions.iter().any(|((from, _), to_states)| from == name || to_states.contains(name))
+        };
+        let mut name = base.to_string();
+        let mut counter = 0;
+        while used(&name) {
+            counter += 1;
+            name = format!("{}{}", base, counter);
+        }
+        name
+    }
+
+    // the states are renamed to L_... and R_..., both start sets are kept
+    pub fn union(&self, other: &NonDeterministicAutomaton) -> NonDeterministicAutomaton {
+        let mut result = self.with_prefix("L");
+        let right = other.with_prefix("R");
+        result.states.extend(right.states);
+        result.alphabet.extend(right.alphabet);
+        result.start_states.extend(right.start_states);
+        result.terminal_states.extend(right.terminal_states);
+        result.transitions.extend(right.transitions);
+        result
+    }
+
+    // eps transitions lead from the terminal states of self to the start states of other
+    pub fn concatenation(&self, other: &NonDeterministicAutomaton) -> NonDeterministicAutomaton {
+        let mut result = self.with_prefix("L");
+        let right = other.with_prefix("R");
+        for terminal in &result.terminal_states.clone() {
+            for start in &right.start_states {
+                result.add_transition(terminal.clone(), "eps".to_string(), start.clone());
+            }
+        }
+        result.states.extend(right.states);
+        result.alphabet.extend(right.alphabet);
+        result.terminal_states = right.terminal_states;
+        result.transitions.extend(right.transitions);
+        result
+    }
+
+    #[doc = r"* a new start state accepts the empty word, it has eps transitions to the
+    * old start states and the old terminal states lead back to it"]
+    pub fn kleene_star(&self) -> NonDeterministicAutomaton {
+        let mut result = self.clone();
+        let start = result.fresh_state("s");
+        for old_start in &self.start_states {
+            result.add_transition(start.clone(), "eps".to_string(), old_start.clone());
+        }
+        for terminal in &self.terminal_states {
+            result.add_transition(terminal.clone(), "eps".to_string(), start.clone());
+        }
+        result.states.insert(start.clone());
+        result.start_states = HashSet::from([start.clone()]);
+        result.terminal_states = HashSet::from([start]);
+        result
+    }
+
+    // one or more repetitions: terminal states get eps transitions to the start states
+    pub fn kleene_plus(&self) -> NonDeterministicAutomaton {
+        let mut result = self.clone();
+        for terminal in &self.terminal_states {
+            for start in &self.start_states {
+                result.add_transition(terminal.clone(), "eps".to_string(), start.clone());
+            }
+        }
+        result
+    }
+
+    // an extra start state that is terminal too
+    pub fn optional(&self) -> NonDeterministicAutomaton {
+        let mut result = self.clone();
+        let start = result.fresh_state("s");
+        result.states.insert(start.clone());
+        result.start_states.insert(start.clone());
+        result.terminal_states.insert(start);
+        result
+    }
+
+    pub fn to_regex(&self) -> Regex {
+        let mut transitions = Vec::new();
+        for ((from, symbol), to_states) in &self.transitions {
+            let label = if symbol == "eps" { Regex::Epsilon } else { Regex::Symbol(symbol.clone()) };
+            for to in to_states {
+                transitions.push((from.clone(), label.clone(), to.clone()));
+            }
+        }
+        eliminate_states(&self.states, &self.start_states, &self.terminal_states, transitions)
+    }
+
     pub fn remove_unreachable_states(&mut self) {
         let mut reachable_nodes: HashSet<String> = HashSet::new();
         let mut productive_nodes: HashSet<String> = HashSet::new();
@@ -232,19 +508,19 @@ impl Automaton for NonDeterministicAutomaton {
 
         for state in &self.states {
             if self.start_states.contains(state) {
-                out_dot_code.push_str(&format!("    i{} [shape=point, style=invis];\n", state));
+                out_dot_code.push_str(&format!("    {} [shape=point, style=invis];\n", dot_id(&format!("i{}", state))));
             }
         }
 
         for state in &self.states {
             if self.terminal_states.contains(state) {
-                out_dot_code.push_str(&format!("    {} [shape=doublecircle];\n", state));
+                out_dot_code.push_str(&format!("    {} [shape=doublecircle];\n", dot_id(state)));
             }
         }
-        out_dot_code.push_str("\n");
+        out_dot_code.push('\n');
 
         for state in &self.start_states {
-            out_dot_code.push_str(&format!("    i{} -> {};\n", state, state));
+            out_dot_code.push_str(&format!("    {} -> {};\n", dot_id(&format!("i{}", state)), dot_id(state)));
         }
 
         let mut edge_map: HashMap<(&str, &str), Vec<&str>> = HashMap::new();
@@ -257,31 +533,49 @@ impl Automaton for NonDeterministicAutomaton {
 
         for ((start, end), labels) in edge_map {
             let label_str = labels.join(", ");
-            out_dot_code.push_str(&format!("    {} -> {} [label=\"{}\"];\n", start, end, label_str));
+            out_dot_code.push_str(&format!("    {} -> {} [label=\"{}\"];\n", dot_id(start), dot_id(end), escape_dot_label(&label_str)));
         }
 
         out_dot_code.push_str("}\n");
         out_dot_code
     }
 
-    fn build_from_file(&mut self, file_name: &str) -> io::Result<()> {
-        let path = Path::new(file_name);
-        let file = File::open(&path)?;
-        let lines = io::BufReader::new(file).lines().collect::<Result<Vec<String>, _>>()?;
+    fn build_from_file(&mut self, file_name: &str) -> Result<(), AutomatonError> {
+        let lines = SourceLines::read(file_name)?;
 
-        self.states = lines[0].split_whitespace().map(String::from).collect();
-        self.alphabet = lines[1].split_whitespace().map(String::from).collect();
-        self.start_states = lines[2].split_whitespace().map(String::from).collect();
-        self.terminal_states = lines[3].split_whitespace().map(String::from).collect();
-        self.transitions.clear();
-        
-        for line in &lines[4..] {
-            let parts: Vec<String> = line.split_whitespace().map(String::from).collect();
-            if parts.len() == 3 {
-                self.add_transition(parts[0].clone(), parts[1].clone(), parts[2].clone());
+        let states: HashSet<String> = lines.section(0, "states")?.into_iter().map(|(_, state)| state).collect();
+        let alphabet: HashSet<String> = lines.section(1, "alphabet")?.into_iter().map(|(_, symbol)| symbol).collect();
+        let start_states = lines.state_section(2, "start states", &states)?;
+        let terminal_states = lines.state_section(3, "terminal states", &states)?;
+
+        // eps transitions are allowed without being part of the alphabet
+        let mut symbols = alphabet.clone();
+        symbols.insert("eps".to_string());
+
+        let mut ndfa = NonDeterministicAutomaton::new();
+        ndfa.states = states;
+        ndfa.alphabet = alphabet;
+        ndfa.start_states = start_states;
+        ndfa.terminal_states = terminal_states;
+
+        for index in 4..lines.len() {
+            let parts = lines.tokens(index);
+            if parts.is_empty() {
+                continue;
             }
+            let [(from_column, from_state), (input_column, input), (to_column, to_state)] = parts.as_slice() else {
+                return Err(AutomatonError::InvalidTransition {
+                    location: lines.location(index, parts[0].0),
+                    description: format!("expected `from symbol to`, found {} fields", parts.len()),
+                });
+            };
+            lines.check_state(index, *from_column, from_state, &ndfa.states)?;
+            lines.check_symbol(index, *input_column, input, &symbols)?;
+            lines.check_state(index, *to_column, to_state, &ndfa.states)?;
+            ndfa.add_transition(from_state.clone(), input.clone(), to_state.clone());
         }
 
+        *self = ndfa;
         Ok(())
     }
 }
@@ -1,20 +1,109 @@
-/**
+/*
  * Gyorgy Matyas
  * gmim2236
  * 1.B.02
  * pda
  */
 
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
 use std::fs::File;
 use std::io::{self, BufRead};
-use std::path::Path;
 
 use crate::traits::Automaton;
-use crate::utils::escape_dot_label;
+use crate::error::{AutomatonError, PushdownBuildError, SourceLines};
+use crate::utils::{escape_dot_label, format_word, split_word};
 
-#[derive(Debug)]
-struct Transition {
+// when a run that consumed the whole input is accepting
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AcceptanceMode {
+    #[default]
+    FinalState,
+    EmptyStack,
+    Both,
+}
+
+impl AcceptanceMode {
+    // name used in the optional `acceptance:` header line of the files
+    pub fn name(self) -> &'static str {
+        match self {
+            AcceptanceMode::FinalState => "final-state",
+            AcceptanceMode::EmptyStack => "empty-stack",
+            AcceptanceMode::Both => "both",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [AcceptanceMode::FinalState, AcceptanceMode::EmptyStack, AcceptanceMode::Both]
+            .into_iter()
+            .find(|mode| mode.name() == name)
+    }
+}
+
+// Undecided: no accepting run was found, but some configurations were cut off
+// by the stack height bound or by the limit on the explored configurations
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AcceptanceResult {
+    Accepted,
+    Rejected,
+    Undecided,
+}
+
+pub const DEFAULT_MAX_STACK_HEIGHT: usize = 64;
+
+// eps moves that can push different symbols give exponentially many stacks,
+// the search gives up after this many configurations
+pub const DEFAULT_MAX_CONFIGURATIONS: usize = 100_000;
+
+// state, number of input symbols read, stack (top is the last element)
+type Configuration = (String, usize, Vec<String>);
+
+struct Search {
+    result: AcceptanceResult,
+    nodes: Vec<(Configuration, Option<(usize, usize)>)>,
+    accepting: Option<usize>,
+}
+
+// the stack is listed top first
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PushdownConfiguration {
+    pub state: String,
+    pub remaining_input: Vec<String>,
+    pub stack: Vec<String>,
+}
+
+impl fmt::Display for PushdownConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.state, format_word(&self.remaining_input), format_word(&self.stack))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PushdownRun {
+    pub configurations: Vec<PushdownConfiguration>,
+    pub transitions: Vec<Transition>,
+}
+
+#[doc = r"* the classic derivation table, one configuration per line:
+    *   (p, aabb, Z)
+    * ⊢ (p, abb, AZ)    (p, a, Z) -> (p, AZ)"]
+impl fmt::Display for PushdownRun {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rendered: Vec<String> = self.configurations.iter().map(ToString::to_string).collect();
+        let width = rendered.iter().map(|configuration| configuration.chars().count()).max().unwrap_or(0);
+        for (index, configuration) in rendered.iter().enumerate() {
+            if index == 0 {
+                writeln!(f, "  {}", configuration)?;
+            } else {
+                writeln!(f, "⊢ {:<width$}    {}", configuration, self.transitions[index - 1])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Transition {
     current_state: String,
     input_symbol: String,
     stack_symbol: String,
@@ -22,7 +111,39 @@ struct Transition {
     next_state: String, 
 }
 
-#[derive(Debug)]
+impl Transition {
+    pub fn current_state(&self) -> &str {
+        &self.current_state
+    }
+
+    // "eps" if no input is read
+    pub fn input_symbol(&self) -> &str {
+        &self.input_symbol
+    }
+
+    pub fn stack_symbol(&self) -> &str {
+        &self.stack_symbol
+    }
+
+    // top first, may contain "eps"
+    pub fn new_stack_symbols(&self) -> &[String] {
+        &self.new_stack_symbols
+    }
+
+    pub fn next_state(&self) -> &str {
+        &self.next_state
+    }
+}
+
+// (p, a, Z) -> (q, AZ), the pushed symbols are listed top first
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pushed: Vec<String> = self.new_stack_symbols.iter().filter(|symbol| *symbol != "eps").cloned().collect();
+        write!(f, "({}, {}, {}) -> ({}, {})", self.current_state, self.input_symbol, self.stack_symbol, self.next_state, format_word(&pushed))
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct PushdownAutomaton {
     states: HashSet<String>,
     input_symbols: HashSet<String>,
@@ -31,6 +152,15 @@ pub struct PushdownAutomaton {
     stack_start_symbol: String,
     terminal_states: HashSet<String>,
     transitions: Vec<Transition>,
+    acceptance_mode: AcceptanceMode,
+    max_stack_height: usize,
+    max_configurations: usize,
+}
+
+impl Default for PushdownAutomaton {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl PushdownAutomaton {
@@ -43,6 +173,81 @@ impl PushdownAutomaton {
             stack_start_symbol: String::new(),
             terminal_states: HashSet::new(),
             transitions: Vec::new(),
+            acceptance_mode: AcceptanceMode::default(),
+            max_stack_height: DEFAULT_MAX_STACK_HEIGHT,
+            max_configurations: DEFAULT_MAX_CONFIGURATIONS,
+        }
+    }
+
+    // files without an `acceptance:` header keep this mode
+    pub fn with_acceptance_mode(acceptance_mode: AcceptanceMode) -> Self {
+        PushdownAutomaton {
+            acceptance_mode,
+            ..Self::new()
+        }
+    }
+
+    pub fn builder() -> PushdownAutomatonBuilder {
+        PushdownAutomatonBuilder::new()
+    }
+
+    pub fn states(&self) -> &HashSet<String> {
+        &self.states
+    }
+
+    pub fn input_symbols(&self) -> &HashSet<String> {
+        &self.input_symbols
+    }
+
+    pub fn stack_symbols(&self) -> &HashSet<String> {
+        &self.stack_symbols
+    }
+
+    pub fn start_state(&self) -> &str {
+        &self.start_state
+    }
+
+    pub fn stack_start_symbol(&self) -> &str {
+        &self.stack_start_symbol
+    }
+
+    pub fn terminal_states(&self) -> &HashSet<String> {
+        &self.terminal_states
+    }
+
+    pub fn transitions(&self) -> &[Transition] {
+        &self.transitions
+    }
+
+    pub fn acceptance_mode(&self) -> AcceptanceMode {
+        self.acceptance_mode
+    }
+
+    pub fn set_acceptance_mode(&mut self, acceptance_mode: AcceptanceMode) {
+        self.acceptance_mode = acceptance_mode;
+    }
+
+    pub fn max_stack_height(&self) -> usize {
+        self.max_stack_height
+    }
+
+    pub fn set_max_stack_height(&mut self, max_stack_height: usize) {
+        self.max_stack_height = max_stack_height;
+    }
+
+    pub fn max_configurations(&self) -> usize {
+        self.max_configurations
+    }
+
+    pub fn set_max_configurations(&mut self, max_configurations: usize) {
+        self.max_configurations = max_configurations;
+    }
+
+    fn is_accepting(&self, state: &str, stack: &[String]) -> bool {
+        match self.acceptance_mode {
+            AcceptanceMode::FinalState => self.terminal_states.contains(state),
+            AcceptanceMode::EmptyStack => stack.is_empty(),
+            AcceptanceMode::Both => self.terminal_states.contains(state) && stack.is_empty(),
         }
     }
 
@@ -51,86 +256,368 @@ impl PushdownAutomaton {
     * 1.B.02
     * pda"]
     pub fn accepts(&self, input: &str) -> bool {
-        let stack = vec![self.stack_start_symbol.clone()];
-        let input_chars: Vec<String> = input.chars().map(String::from).collect();
-        
-        // check for valid characters
-        if !input_chars.iter().all(|c| self.input_symbols.contains(c)) {
-            return false;
-        }
-        
-        self.dfs_accept(&self.start_state, &input_chars, &stack, 0)
+        self.decide(input) == AcceptanceResult::Accepted
     }
 
-    fn dfs_accept(
-        &self,
-        current_state: &str,
-        input_chars: &[String],
-        stack: &[String],
-        iteration_count: usize,
-    ) -> bool {
-        const MAX_ITERATIONS: usize = 10000;
-        
-        if iteration_count >= MAX_ITERATIONS {
-            return false;
-        }
+    #[doc = r"* breadth first search over the (state, input position, stack) configurations
+    * every configuration is visited once, so eps loops terminate, and the ones
+      with a stack higher than max_stack_height are not explored
+    * at most max_configurations configurations are explored
+    * Rejected only if the whole configuration graph was explored"]
+    pub fn decide(&self, input: &str) -> AcceptanceResult {
+        self.search(&split_word(input, &self.input_symbols)).result
+    }
+
+    #[doc = r"* an accepting computation with the fewest transitions
+    * None if the word is not accepted or no accepting run was found within the bounds
+    * configurations[i + 1] follows from configurations[i] by transitions[i]"]
+    pub fn accepting_run(&self, input: &str) -> Option<PushdownRun> {
+        let word = split_word(input, &self.input_symbols);
+        let search = self.search(&word);
+        let mut node = search.accepting?;
 
-        // accept condition
-        if input_chars.is_empty() && (self.terminal_states.contains(current_state) || stack.is_empty())  {
-        //if self.terminal_states.contains(current_state) {
-            return true;
+        let mut configurations = Vec::new();
+        let mut transitions = Vec::new();
+        loop {
+            let ((state, position, stack), parent) = &search.nodes[node];
+            configurations.push(PushdownConfiguration {
+                state: state.clone(),
+                remaining_input: word[*position..].to_vec(),
+                stack: stack.iter().rev().cloned().collect(),
+            });
+            let Some((previous, transition)) = parent else {
+                break;
+            };
+            transitions.push(self.transitions[*transition].clone());
+            node = *previous;
         }
+        configurations.reverse();
+        transitions.reverse();
+        Some(PushdownRun { configurations, transitions })
+    }
 
-        // cant proceed if stack is empty
-        if stack.is_empty() {
-            return false;
+    // bfs with parent pointers (node index, transition index) for the runs
+    fn search(&self, word: &[String]) -> Search {
+        let mut search = Search { result: AcceptanceResult::Rejected, nodes: Vec::new(), accepting: None };
+        if !word.iter().all(|symbol| self.input_symbols.contains(symbol)) {
+            return search;
         }
 
-        let current_input = input_chars.first().map_or("eps", String::as_str);
-        let current_stack_top = stack.last().expect("Stack should not be empty here");
+        let start = (self.start_state.clone(), 0, vec![self.stack_start_symbol.clone()]);
+        let mut visited: HashSet<Configuration> = HashSet::from([start.clone()]);
+        search.nodes.push((start, None));
+        let mut queue: VecDeque<usize> = VecDeque::from([0]);
+        let mut bounded = false;
 
-        for transition in &self.transitions {
-            if transition.current_state == current_state
-                && (transition.input_symbol == current_input || transition.input_symbol == "eps")
-                && transition.stack_symbol == *current_stack_top
-            {
-                // new stack
-                let mut new_stack = stack[..stack.len()-1].to_vec();
+        while let Some(node) = queue.pop_front() {
+            let (state, position, stack) = &search.nodes[node].0;
+            if *position == word.len() && self.is_accepting(state, stack) {
+                search.result = AcceptanceResult::Accepted;
+                search.accepting = Some(node);
+                return search;
+            }
+
+            // cant proceed if stack is empty
+            let Some(top) = stack.last() else {
+                continue;
+            };
+
+            let mut successors = Vec::new();
+            for (index, transition) in self.transitions.iter().enumerate() {
+                if transition.current_state != *state || transition.stack_symbol != *top {
+                    continue;
+                }
+                let next_position = if transition.input_symbol == "eps" {
+                    *position
+                } else if word.get(*position) == Some(&transition.input_symbol) {
+                    position + 1
+                } else {
+                    continue;
+                };
+
+                let mut next_stack = stack[..stack.len() - 1].to_vec();
                 for symbol in transition.new_stack_symbols.iter().rev() {
                     if symbol != "eps" {
-                        new_stack.push(symbol.clone());
+                        next_stack.push(symbol.clone());
                     }
                 }
+                if next_stack.len() > self.max_stack_height {
+                    bounded = true;
+                    continue;
+                }
 
-                //  new input
-                let new_input = if transition.input_symbol != "eps" && !input_chars.is_empty() {
-                    &input_chars[1..]
-                } else {
-                    input_chars
-                };
+                let next = (transition.next_state.clone(), next_position, next_stack);
+                if visited.contains(&next) {
+                    continue;
+                }
+                if visited.len() >= self.max_configurations {
+                    bounded = true;
+                    continue;
+                }
+                visited.insert(next.clone());
+                successors.push((next, index));
+            }
 
-                if self.dfs_accept(&transition.next_state, new_input, &new_stack, iteration_count + 1) {
-                    return true;
+            for (next, transition) in successors {
+                search.nodes.push((next, Some((node, transition))));
+                queue.push_back(search.nodes.len() - 1);
+            }
+        }
+
+        if bounded {
+            search.result = AcceptanceResult::Undecided;
+        }
+        search
+    }
+
+    #[doc = r"* the first `max_count` accepted words not longer than `max_length`
+    * in shortlex order, every word over the input alphabet is tried"]
+    pub fn accepted_words(&self, max_length: usize, max_count: usize) -> Vec<String> {
+        let mut symbols: Vec<&String> = self.input_symbols.iter().collect();
+        symbols.sort();
+
+        let mut accepted = Vec::new();
+        let mut current_length: Vec<String> = vec![String::new()];
+        for length in 0..=max_length {
+            for word in &current_length {
+                if accepted.len() == max_count {
+                    return accepted;
+                }
+                if self.accepts(word) {
+                    accepted.push(word.clone());
                 }
             }
+            if length < max_length {
+                current_length = current_length.iter()
+                    .flat_map(|word| symbols.iter().map(move |symbol| format!("{}{}", word, symbol)))
+                    .collect();
+            }
         }
-        false
+        accepted
+    }
+
+    // name not used yet by any state or stack symbol
+    fn fresh_name(&self, base: &str) -> String {
+        let mut name = base.to_string();
+        while self.states.contains(&name) || self.stack_symbols.contains(&name) {
+            name.push('\'');
+        }
+        name
+    }
+
+    fn add_transition(&mut self, current_state: &str, input_symbol: &str, stack_symbol: &str, new_stack_symbols: &[&str], next_state: &str) {
+        self.transitions.push(Transition {
+            current_state: current_state.to_string(),
+            input_symbol: input_symbol.to_string(),
+            stack_symbol: stack_symbol.to_string(),
+            new_stack_symbols: new_stack_symbols.iter().map(|symbol| symbol.to_string()).collect(),
+            next_state: next_state.to_string(),
+        });
+    }
+
+    // new start state and bottom symbol under the old one, returns (start, bottom)
+    fn with_new_bottom(&mut self) -> (String, String) {
+        let start = self.fresh_name("start");
+        self.states.insert(start.clone());
+        let bottom = self.fresh_name("bottom");
+        self.stack_symbols.insert(bottom.clone());
+
+        let (old_start, old_bottom) = (self.start_state.clone(), self.stack_start_symbol.clone());
+        self.add_transition(&start, "eps", &bottom, &[&old_bottom, &bottom], &old_start);
+        self.start_state = start.clone();
+        self.stack_start_symbol = bottom.clone();
+        (start, bottom)
+    }
+
+    #[doc = r"* equivalent automaton accepting by final state
+    * a new bottom symbol shows when the original stack became empty, from
+      there an eps move leads to the new final state"]
+    pub fn to_final_state_acceptance(&self) -> PushdownAutomaton {
+        let mut pda = self.clone();
+        pda.acceptance_mode = AcceptanceMode::FinalState;
+        if self.acceptance_mode == AcceptanceMode::FinalState {
+            return pda;
+        }
+
+        let (_, bottom) = pda.with_new_bottom();
+        let accept = pda.fresh_name("accept");
+        pda.states.insert(accept.clone());
+        let mut states: Vec<String> = self.states.iter().cloned().collect();
+        states.sort();
+        for state in states {
+            if self.acceptance_mode == AcceptanceMode::EmptyStack || self.terminal_states.contains(&state) {
+                pda.add_transition(&state, "eps", &bottom, &[&bottom], &accept);
+            }
+        }
+        pda.terminal_states = HashSet::from([accept]);
+        pda
+    }
+
+    #[doc = r"* equivalent automaton accepting by empty stack
+    * a new bottom symbol keeps the stack from emptying too early, final states
+      move to a state that pops everything"]
+    pub fn to_empty_stack_acceptance(&self) -> PushdownAutomaton {
+        let mut pda = self.clone();
+        pda.acceptance_mode = AcceptanceMode::EmptyStack;
+        if self.acceptance_mode == AcceptanceMode::EmptyStack {
+            return pda;
+        }
+
+        let (_, bottom) = pda.with_new_bottom();
+        let drain = pda.fresh_name("drain");
+        pda.states.insert(drain.clone());
+        let mut terminal_states: Vec<String> = self.terminal_states.iter().cloned().collect();
+        terminal_states.sort();
+        let mut stack_symbols: Vec<String> = pda.stack_symbols.iter().cloned().collect();
+        stack_symbols.sort();
+
+        for state in &terminal_states {
+            if self.acceptance_mode == AcceptanceMode::Both {
+                // the original stack has to be empty already
+                pda.add_transition(state, "eps", &bottom, &["eps"], &drain);
+            } else {
+                for symbol in &stack_symbols {
+                    pda.add_transition(state, "eps", symbol, &["eps"], &drain);
+                }
+            }
+        }
+        for symbol in &stack_symbols {
+            pda.add_transition(&drain, "eps", symbol, &["eps"], &drain);
+        }
+        pda.terminal_states.clear();
+        pda
     }
 
     pub fn check_for_file(&self, file_name: &str) -> io::Result<()> {
         let file = File::open(file_name)?;
         for line in io::BufReader::new(file).lines() {
             let line = line?;
-            if self.accepts(&line) {
-                println!("{} accepted", &line);
-            } else {
-                println!("{} declined", &line);
+            match self.decide(&line) {
+                AcceptanceResult::Accepted => println!("{} accepted", &line),
+                AcceptanceResult::Rejected => println!("{} declined", &line),
+                AcceptanceResult::Undecided => println!("{} undecided", &line),
             }
         }
         Ok(())
     }
 }
 
+#[doc = r"* builds a PushdownAutomaton in code, the checks of build_from_file are
+    * done by build
+    * push strings are split like words: whitespace separated, or longest
+      stack symbol match, eps pushes nothing"]
+#[derive(Clone, Debug, Default)]
+pub struct PushdownAutomatonBuilder {
+    automaton: PushdownAutomaton,
+    start_state: Option<String>,
+    stack_start_symbol: Option<String>,
+    transitions: Vec<(String, String, String, String, String)>,
+}
+
+impl PushdownAutomatonBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_state(mut self, state: &str) -> Self {
+        self.automaton.states.insert(state.to_string());
+        self
+    }
+
+    pub fn add_input_symbol(mut self, symbol: &str) -> Self {
+        self.automaton.input_symbols.insert(symbol.to_string());
+        self
+    }
+
+    pub fn add_stack_symbol(mut self, symbol: &str) -> Self {
+        self.automaton.stack_symbols.insert(symbol.to_string());
+        self
+    }
+
+    pub fn start_state(mut self, state: &str) -> Self {
+        self.start_state = Some(state.to_string());
+        self
+    }
+
+    pub fn stack_start_symbol(mut self, symbol: &str) -> Self {
+        self.stack_start_symbol = Some(symbol.to_string());
+        self
+    }
+
+    pub fn add_terminal_state(mut self, state: &str) -> Self {
+        self.automaton.terminal_states.insert(state.to_string());
+        self
+    }
+
+    pub fn acceptance_mode(mut self, acceptance_mode: AcceptanceMode) -> Self {
+        self.automaton.acceptance_mode = acceptance_mode;
+        self
+    }
+
+    pub fn max_stack_height(mut self, max_stack_height: usize) -> Self {
+        self.automaton.max_stack_height = max_stack_height;
+        self
+    }
+
+    pub fn max_configurations(mut self, max_configurations: usize) -> Self {
+        self.automaton.max_configurations = max_configurations;
+        self
+    }
+
+    // from --input, stack_symbol / push--> to
+    pub fn add_transition(mut self, from: &str, input: &str, stack_symbol: &str, push: &str, to: &str) -> Self {
+        self.transitions.push((from.to_string(), input.to_string(), stack_symbol.to_string(), push.to_string(), to.to_string()));
+        self
+    }
+
+    pub fn build(self) -> Result<PushdownAutomaton, PushdownBuildError> {
+        let mut automaton = self.automaton;
+        for symbol in automaton.input_symbols.iter().chain(&automaton.stack_symbols) {
+            if symbol == "eps" {
+                return Err(PushdownBuildError::ReservedSymbol(symbol.clone()));
+            }
+        }
+
+        let check_state = |state: &String| {
+            if automaton.states.contains(state) { Ok(()) } else { Err(PushdownBuildError::UnknownState(state.clone())) }
+        };
+        let check_stack_symbol = |symbol: &String| {
+            if automaton.stack_symbols.contains(symbol) { Ok(()) } else { Err(PushdownBuildError::UnknownStackSymbol(symbol.clone())) }
+        };
+
+        let start_state = self.start_state.ok_or(PushdownBuildError::MissingStartState)?;
+        check_state(&start_state)?;
+        let stack_start_symbol = self.stack_start_symbol.ok_or(PushdownBuildError::MissingStackStartSymbol)?;
+        check_stack_symbol(&stack_start_symbol)?;
+        automaton.terminal_states.iter().try_for_each(check_state)?;
+
+        let mut transitions = Vec::new();
+        for (from, input, stack_symbol, push, to) in self.transitions {
+            check_state(&from)?;
+            check_state(&to)?;
+            if input != "eps" && !automaton.input_symbols.contains(&input) {
+                return Err(PushdownBuildError::UnknownInputSymbol(input));
+            }
+            check_stack_symbol(&stack_symbol)?;
+            let new_stack_symbols = if push.trim() == "eps" { Vec::new() } else { split_word(&push, &automaton.stack_symbols) };
+            new_stack_symbols.iter().try_for_each(check_stack_symbol)?;
+
+            transitions.push(Transition {
+                current_state: from,
+                input_symbol: input,
+                stack_symbol,
+                new_stack_symbols,
+                next_state: to,
+            });
+        }
+
+        automaton.start_state = start_state;
+        automaton.stack_start_symbol = stack_start_symbol;
+        automaton.transitions = transitions;
+        Ok(automaton)
+    }
+}
+
 impl Automaton for PushdownAutomaton {
     fn build_dot_code(&self) -> String {
         let mut dot_content = String::new();
@@ -163,53 +650,91 @@ impl Automaton for PushdownAutomaton {
         dot_content
     }
 
-    fn build_from_file(&mut self, file_name: &str) -> io::Result<()> {
-        let path = Path::new(file_name);
-        let file = File::open(&path)?;
-        let lines: Vec<String> = io::BufReader::new(file)
-            .lines()
-            .collect::<Result<_, _>>()?;
+    fn build_from_file(&mut self, file_name: &str) -> Result<(), AutomatonError> {
+        let lines = SourceLines::read(file_name)?;
 
-        if lines.len() < 6 {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "File does not contain enough lines",
-            ));
+        // optional header line before the states: `acceptance: final-state|empty-stack|both`
+        let mut acceptance_mode = self.acceptance_mode;
+        let mut offset = 0;
+        if let Some((_, first)) = lines.section(0, "states")?.first() {
+            if first == "acceptance:" {
+                let header = lines.tokens(0);
+                let Some(mode) = header.get(1).and_then(|(_, name)| AcceptanceMode::from_name(name)) else {
+                    return Err(AutomatonError::InvalidHeader {
+                        location: lines.location(0, header.get(1).map_or(1, |(column, _)| *column)),
+                        description: "expected `acceptance: final-state|empty-stack|both`".to_string(),
+                    });
+                };
+                acceptance_mode = mode;
+                offset = 1;
+            }
         }
 
         //1. sor: allapotok, szokozokkel elvalasztva
-        self.states = lines[0].split_whitespace().map(String::from).collect();
+        let states: HashSet<String> = lines.section(offset, "states")?.into_iter().map(|(_, state)| state).collect();
         //2. sor: bemeneti abece elemei, szokozokkel elvalasztva
-        self.input_symbols = lines[1].split_whitespace().map(String::from).collect();
+        let input_symbols: HashSet<String> = lines.section(offset + 1, "input symbols")?.into_iter().map(|(_, symbol)| symbol).collect();
         //3. sor: veremabece elemei, szokozokkel elvalasztva
-        self.stack_symbols = lines[2].split_whitespace().map(String::from).collect();
+        let stack_symbols: HashSet<String> = lines.section(offset + 2, "stack symbols")?.into_iter().map(|(_, symbol)| symbol).collect();
         // 4. sor: kezdoallapot
-        self.start_state = lines[3].trim().to_string();
+        let Some((start_column, start_state)) = lines.section(offset + 3, "start state")?.into_iter().next() else {
+            return Err(AutomatonError::MissingSection { location: lines.location(offset + 3, 1), section: "start state".to_string() });
+        };
+        lines.check_state(offset + 3, start_column, &start_state, &states)?;
         // 5. sor: veremmemoria kezdojele
-        self.stack_start_symbol = lines[4].trim().to_string();
+        let Some((bottom_column, stack_start_symbol)) = lines.section(offset + 4, "stack start symbol")?.into_iter().next() else {
+            return Err(AutomatonError::MissingSection { location: lines.location(offset + 4, 1), section: "stack start symbol".to_string() });
+        };
+        lines.check_symbol(offset + 4, bottom_column, &stack_start_symbol, &stack_symbols)?;
         // 6. sor: vegallapotok, szokozokkel elvalasztva
-        self.terminal_states = lines[5].split_whitespace().map(String::from).collect();
-        
+        let terminal_states = lines.state_section(offset + 5, "terminal states", &states)?;
+
+        // eps: no input read / nothing pushed
+        let mut inputs = input_symbols.clone();
+        inputs.insert("eps".to_string());
+        let mut pushed = stack_symbols.clone();
+        pushed.insert("eps".to_string());
+
         //egy-egy atmenet
-        self.transitions.clear();
-        for line in &lines[6..] {
-            let parts: Vec<String> = line.split_whitespace().map(String::from).collect();
+        let mut transitions = Vec::new();
+        for index in offset + 6..lines.len() {
+            let parts = lines.tokens(index);
+            if parts.is_empty() {
+                continue;
+            }
             if parts.len() < 5 {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    format!("Invalid transition format: {}", line),
-                ));
+                return Err(AutomatonError::InvalidTransition {
+                    location: lines.location(index, parts[0].0),
+                    description: format!("expected `from input stacktop push... to`, found {} fields", parts.len()),
+                });
+            }
+
+            let (next_column, next_state) = &parts[parts.len() - 1];
+            lines.check_state(index, parts[0].0, &parts[0].1, &states)?;
+            lines.check_symbol(index, parts[1].0, &parts[1].1, &inputs)?;
+            lines.check_symbol(index, parts[2].0, &parts[2].1, &stack_symbols)?;
+            for (column, symbol) in &parts[3..parts.len() - 1] {
+                lines.check_symbol(index, *column, symbol, &pushed)?;
             }
+            lines.check_state(index, *next_column, next_state, &states)?;
 
-            self.transitions.push(Transition {
-                current_state: parts[0].clone(),
-                input_symbol: parts[1].clone(),
-                stack_symbol: parts[2].clone(),
-                new_stack_symbols: parts[3..parts.len()-1].to_vec(),
-                next_state: parts[parts.len()-1].clone(),
+            transitions.push(Transition {
+                current_state: parts[0].1.clone(),
+                input_symbol: parts[1].1.clone(),
+                stack_symbol: parts[2].1.clone(),
+                new_stack_symbols: parts[3..parts.len() - 1].iter().map(|(_, symbol)| symbol.clone()).collect(),
+                next_state: next_state.clone(),
             });
         }
 
+        self.states = states;
+        self.input_symbols = input_symbols;
+        self.stack_symbols = stack_symbols;
+        self.start_state = start_state;
+        self.stack_start_symbol = stack_start_symbol;
+        self.terminal_states = terminal_states;
+        self.transitions = transitions;
+        self.acceptance_mode = acceptance_mode;
         Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,515 @@
+/*
+ * regex
+ * parsing + thompson construction into ndfa
+ * state elimination back into regex
+ */
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::io;
+
+use crate::nondeterministic::NonDeterministicAutomaton;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Regex {
+    Empty,
+    Epsilon,
+    Symbol(String),
+    Union(Box<Regex>, Box<Regex>),
+    Concat(Box<Regex>, Box<Regex>),
+    Star(Box<Regex>),
+    Plus(Box<Regex>),
+    Optional(Box<Regex>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Symbol(String),
+    Epsilon,
+    Empty,
+    Union,
+    Star,
+    Plus,
+    Optional,
+    LeftParen,
+    RightParen,
+}
+
+fn invalid_pattern(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// true if "eps" can be written as a sequence of alphabet symbols
+fn alphabet_spells_eps(alphabet: &HashSet<String>) -> bool {
+    let mut reachable = [true, false, false, false];
+    for start in 0..3 {
+        if reachable[start] {
+            for symbol in alphabet {
+                if !symbol.is_empty() && "eps"[start..].starts_with(symbol.as_str()) {
+                    reachable[start + symbol.len()] = true;
+                }
+            }
+        }
+    }
+    reachable[3]
+}
+
+// splits the pattern into tokens, alphabet symbols are matched longest first
+// so multi character symbols work too
+// the eps keyword is disabled if the alphabet can spell it, ε still works then
+fn tokenize(pattern: &str, alphabet: &HashSet<String>) -> io::Result<Vec<(Token, usize)>> {
+    let mut symbols: Vec<&String> = alphabet.iter().collect();
+    symbols.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+    let eps_keyword = !alphabet_spells_eps(alphabet);
+
+    let mut tokens = Vec::new();
+    let mut rest = pattern;
+    let mut position = 0;
+
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            position += 1;
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let (token, length) = if let Some(token) = match c {
+            '|' => Some(Token::Union),
+            '*' => Some(Token::Star),
+            '+' => Some(Token::Plus),
+            '?' => Some(Token::Optional),
+            '(' => Some(Token::LeftParen),
+            ')' => Some(Token::RightParen),
+            'ε' => Some(Token::Epsilon),
+            '∅' => Some(Token::Empty),
+            _ => None,
+        } {
+            (token, c.len_utf8())
+        } else if eps_keyword && rest.starts_with("eps") {
+            (Token::Epsilon, 3)
+        } else if let Some(symbol) = symbols.iter().find(|s| !s.is_empty() && rest.starts_with(s.as_str())) {
+            (Token::Symbol(symbol.to_string()), symbol.len())
+        } else {
+            return Err(invalid_pattern(format!("Unknown symbol {:?} at position {}", c, position)));
+        };
+
+        tokens.push((token, position));
+        position += rest[..length].chars().count();
+        rest = &rest[length..];
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(token, _)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.index).map_or(self.end, |(_, position)| *position)
+    }
+
+    // union := concat ('|' concat)*
+    fn parse_union(&mut self) -> io::Result<Regex> {
+        let mut left = self.parse_concat()?;
+        while self.peek() == Some(&Token::Union) {
+            self.index += 1;
+            let right = self.parse_concat()?;
+            left = Regex::Union(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    // concat := postfix postfix*
+    fn parse_concat(&mut self) -> io::Result<Regex> {
+        let mut left = self.parse_postfix()?;
+        while matches!(
+            self.peek(),
+            Some(Token::Symbol(_)) | Some(Token::Epsilon) | Some(Token::Empty) | Some(Token::LeftParen)
+        ) {
+            let right = self.parse_postfix()?;
+            left = Regex::Concat(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    // postfix := atom ('*' | '+' | '?')*
+    fn parse_postfix(&mut self) -> io::Result<Regex> {
+        let mut inner = self.parse_atom()?;
+        loop {
+            inner = match self.peek() {
+                Some(Token::Star) => Regex::Star(Box::new(inner)),
+                Some(Token::Plus) => Regex::Plus(Box::new(inner)),
+                Some(Token::Optional) => Regex::Optional(Box::new(inner)),
+                _ => return Ok(inner),
+            };
+            self.index += 1;
+        }
+    }
+
+    // atom := symbol | eps | empty | '(' union ')'
+    fn parse_atom(&mut self) -> io::Result<Regex> {
+        let position = self.position();
+        let token = self.peek().cloned();
+        self.index += 1;
+
+        match token {
+            Some(Token::Symbol(symbol)) => Ok(Regex::Symbol(symbol)),
+            Some(Token::Epsilon) => Ok(Regex::Epsilon),
+            Some(Token::Empty) => Ok(Regex::Empty),
+            Some(Token::LeftParen) => {
+                let inner = self.parse_union()?;
+                if self.peek() != Some(&Token::RightParen) {
+                    return Err(invalid_pattern(format!("Expected ')' at position {}", self.position())));
+                }
+                self.index += 1;
+                Ok(inner)
+            }
+            _ => Err(invalid_pattern(format!("Expected expression at position {}", position))),
+        }
+    }
+}
+
+impl Regex {
+    #[doc = r"* parses a regex over the given alphabet
+    * operators: | (union), * + ? (postfix), ( ) grouping
+    * eps or ε is the empty word, ∅ is the empty set
+    * over alphabets whose symbols can spell eps (like a..z) only ε is the empty word"]
+    pub fn parse(pattern: &str, alphabet: &HashSet<String>) -> io::Result<Regex> {
+        let tokens = tokenize(pattern, alphabet)?;
+        let mut parser = Parser {
+            tokens,
+            index: 0,
+            end: pattern.chars().count(),
+        };
+
+        let regex = parser.parse_union()?;
+        if parser.index < parser.tokens.len() {
+            return Err(invalid_pattern(format!("Unexpected token at position {}", parser.position())));
+        }
+
+        Ok(regex)
+    }
+
+    #[doc = r"* thompson construction
+    * one start and one terminal state, states are numbered from 0"]
+    pub fn to_nondeterministic(&self, alphabet: &HashSet<String>) -> NonDeterministicAutomaton {
+        let mut builder = ThompsonBuilder {
+            ndfa: NonDeterministicAutomaton::new(),
+            state_counter: 0,
+        };
+
+        let (start, end) = builder.build(self);
+        let mut ndfa = builder.ndfa;
+        ndfa.alphabet = alphabet.clone();
+        ndfa.start_states.insert(start);
+        ndfa.terminal_states.insert(end);
+
+        ndfa
+    }
+
+    // simplifying constructors, used by state elimination to keep the output short
+    pub fn union(left: Regex, right: Regex) -> Regex {
+        match (left, right) {
+            (Regex::Empty, other) | (other, Regex::Empty) => other,
+            (left, right) if left == right => left,
+            (Regex::Epsilon, other) | (other, Regex::Epsilon) => match other {
+                _ if other.is_nullable() => other,
+                Regex::Plus(inner) => Regex::Star(inner),
+                other => Regex::Optional(Box::new(other)),
+            },
+            (left, right) => Regex::Union(Box::new(left), Box::new(right)),
+        }
+    }
+
+    pub fn concat(left: Regex, right: Regex) -> Regex {
+        match (left, right) {
+            (Regex::Empty, _) | (_, Regex::Empty) => Regex::Empty,
+            (Regex::Epsilon, other) | (other, Regex::Epsilon) => other,
+            // x x* and x* x are both x+
+            (left, Regex::Star(inner)) if left == *inner => Regex::Plus(inner),
+            (Regex::Star(inner), right) if right == *inner => Regex::Plus(inner),
+            (Regex::Concat(first, last), Regex::Star(inner)) if *last == *inner => {
+                Regex::Concat(first, Box::new(Regex::Plus(inner)))
+            }
+            (left, right) => Regex::Concat(Box::new(left), Box::new(right)),
+        }
+    }
+
+    pub fn star(inner: Regex) -> Regex {
+        match inner {
+            Regex::Empty | Regex::Epsilon => Regex::Epsilon,
+            Regex::Star(inner) | Regex::Plus(inner) | Regex::Optional(inner) => Regex::Star(inner),
+            inner => Regex::Star(Box::new(inner)),
+        }
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        match self {
+            Regex::Empty | Regex::Symbol(_) => false,
+            Regex::Epsilon | Regex::Star(_) | Regex::Optional(_) => true,
+            Regex::Union(left, right) => left.is_nullable() || right.is_nullable(),
+            Regex::Concat(left, right) => left.is_nullable() && right.is_nullable(),
+            Regex::Plus(inner) => inner.is_nullable(),
+        }
+    }
+
+    // number of nodes in the syntax tree
+    pub fn size(&self) -> usize {
+        match self {
+            Regex::Empty | Regex::Epsilon | Regex::Symbol(_) => 1,
+            Regex::Union(left, right) | Regex::Concat(left, right) => 1 + left.size() + right.size(),
+            Regex::Star(inner) | Regex::Plus(inner) | Regex::Optional(inner) => 1 + inner.size(),
+        }
+    }
+
+    fn collect_symbols(&self, symbols: &mut HashSet<String>) {
+        match self {
+            Regex::Empty | Regex::Epsilon => {}
+            Regex::Symbol(symbol) => {
+                symbols.insert(symbol.clone());
+            }
+            Regex::Union(left, right) | Regex::Concat(left, right) => {
+                left.collect_symbols(symbols);
+                right.collect_symbols(symbols);
+            }
+            Regex::Star(inner) | Regex::Plus(inner) | Regex::Optional(inner) => inner.collect_symbols(symbols),
+        }
+    }
+
+    #[doc = r"* text that parses back to the same regex over the given alphabet
+    * concatenation is written with spaces if a symbol of the alphabet is longer
+      than one character, or if the alphabet can spell eps (then ε is written
+      for the empty word, see parse)"]
+    pub fn to_string_over(&self, alphabet: &HashSet<String>) -> String {
+        let spells_eps = alphabet_spells_eps(alphabet);
+        let long_symbol = alphabet.iter().any(|symbol| symbol.chars().count() > 1);
+        let separator = if long_symbol || spells_eps { " " } else { "" };
+        let epsilon = if spells_eps { "ε" } else { "eps" };
+
+        let mut text = String::new();
+        self.write_with_precedence(&mut text, 0, separator, epsilon).unwrap();
+        text
+    }
+
+    // union < concatenation < postfix operators < atoms
+    fn precedence(&self) -> u8 {
+        match self {
+            Regex::Union(_, _) => 0,
+            Regex::Concat(_, _) => 1,
+            Regex::Star(_) | Regex::Plus(_) | Regex::Optional(_) => 2,
+            Regex::Empty | Regex::Epsilon | Regex::Symbol(_) => 3,
+        }
+    }
+
+    fn write_with_precedence(&self, f: &mut dyn fmt::Write, min_precedence: u8, separator: &str, epsilon: &str) -> fmt::Result {
+        let parenthesized = self.precedence() < min_precedence;
+        if parenthesized {
+            write!(f, "(")?;
+        }
+
+        match self {
+            Regex::Empty => write!(f, "∅")?,
+            Regex::Epsilon => write!(f, "{}", epsilon)?,
+            Regex::Symbol(symbol) => write!(f, "{}", symbol)?,
+            Regex::Union(left, right) => {
+                left.write_with_precedence(f, 0, separator, epsilon)?;
+                write!(f, "|")?;
+                right.write_with_precedence(f, 0, separator, epsilon)?;
+            }
+            Regex::Concat(left, right) => {
+                left.write_with_precedence(f, 1, separator, epsilon)?;
+                write!(f, "{}", separator)?;
+                right.write_with_precedence(f, 1, separator, epsilon)?;
+            }
+            Regex::Star(inner) | Regex::Plus(inner) | Regex::Optional(inner) => {
+                inner.write_with_precedence(f, 2, separator, epsilon)?;
+                match self {
+                    Regex::Star(_) => write!(f, "*")?,
+                    Regex::Plus(_) => write!(f, "+")?,
+                    _ => write!(f, "?")?,
+                }
+            }
+        }
+
+        if parenthesized {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+// only parenthesizes where the precedence requires it, spacing is chosen from
+// the symbols of the regex, use to_string_over when the alphabet is larger
+impl fmt::Display for Regex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut symbols = HashSet::new();
+        self.collect_symbols(&mut symbols);
+        write!(f, "{}", self.to_string_over(&symbols))
+    }
+}
+
+#[doc = r"* state elimination on a generalized nfa
+* a new start and terminal state are added, then the original states are
+  eliminated one by one, always picking the one with the smallest weight
+  (the growth of the regex size caused by removing it)"]
+pub(crate) fn eliminate_states(
+    states: &HashSet<String>,
+    start_states: &HashSet<String>,
+    terminal_states: &HashSet<String>,
+    transitions: Vec<(String, Regex, String)>,
+) -> Regex {
+    // sorted indices so the output does not depend on hashset order
+    let mut names: Vec<String> = states.iter().chain(start_states).chain(terminal_states).cloned().collect();
+    for (from, _, to) in &transitions {
+        names.push(from.clone());
+        names.push(to.clone());
+    }
+    names.sort();
+    names.dedup();
+
+    let index_of = |state: &String| names.binary_search(state).unwrap();
+    let start = names.len();
+    let end = names.len() + 1;
+
+    let mut edges: BTreeMap<(usize, usize), Regex> = BTreeMap::new();
+    let mut sorted_transitions = transitions;
+    sorted_transitions.sort_by(|a, b| (&a.0, &a.2, a.1.to_string()).cmp(&(&b.0, &b.2, b.1.to_string())));
+    for (from, regex, to) in sorted_transitions {
+        add_edge(&mut edges, index_of(&from), index_of(&to), regex);
+    }
+    for state in start_states {
+        add_edge(&mut edges, start, index_of(state), Regex::Epsilon);
+    }
+    for state in terminal_states {
+        add_edge(&mut edges, index_of(state), end, Regex::Epsilon);
+    }
+
+    let mut remaining: Vec<usize> = (0..names.len()).collect();
+    while !remaining.is_empty() {
+        let weight = |state: usize| {
+            let loop_size = edges.get(&(state, state)).map_or(0, Regex::size);
+            let incoming: Vec<usize> = edges.iter()
+                .filter(|((from, to), _)| *to == state && *from != state)
+                .map(|(_, regex)| regex.size())
+                .collect();
+            let outgoing: Vec<usize> = edges.iter()
+                .filter(|((from, to), _)| *from == state && *to != state)
+                .map(|(_, regex)| regex.size())
+                .collect();
+            let (in_count, out_count) = (incoming.len(), outgoing.len());
+            incoming.iter().sum::<usize>() * out_count.saturating_sub(1)
+                + outgoing.iter().sum::<usize>() * in_count.saturating_sub(1)
+                + loop_size * (in_count * out_count).saturating_sub(1)
+        };
+
+        let (position, &state) = remaining.iter().enumerate()
+            .min_by_key(|(_, &state)| weight(state))
+            .unwrap();
+        remaining.remove(position);
+
+        let self_loop = edges.remove(&(state, state)).map_or(Regex::Epsilon, Regex::star);
+        let incoming: Vec<(usize, Regex)> = edges.iter()
+            .filter(|((_, to), _)| *to == state)
+            .map(|((from, _), regex)| (*from, regex.clone()))
+            .collect();
+        let outgoing: Vec<(usize, Regex)> = edges.iter()
+            .filter(|((from, _), _)| *from == state)
+            .map(|((_, to), regex)| (*to, regex.clone()))
+            .collect();
+        edges.retain(|(from, to), _| *from != state && *to != state);
+
+        for (from, in_regex) in &incoming {
+            for (to, out_regex) in &outgoing {
+                let path = Regex::concat(Regex::concat(in_regex.clone(), self_loop.clone()), out_regex.clone());
+                add_edge(&mut edges, *from, *to, path);
+            }
+        }
+    }
+
+    edges.remove(&(start, end)).unwrap_or(Regex::Empty)
+}
+
+// parallel edges are merged with union
+fn add_edge(edges: &mut BTreeMap<(usize, usize), Regex>, from: usize, to: usize, regex: Regex) {
+    let merged = match edges.remove(&(from, to)) {
+        Some(existing) => Regex::union(existing, regex),
+        None => regex,
+    };
+    edges.insert((from, to), merged);
+}
+
+struct ThompsonBuilder {
+    ndfa: NonDeterministicAutomaton,
+    state_counter: usize,
+}
+
+impl ThompsonBuilder {
+    fn new_state(&mut self) -> String {
+        let state = self.state_counter.to_string();
+        self.state_counter += 1;
+        self.ndfa.states.insert(state.clone());
+        state
+    }
+
+    fn epsilon(&mut self, from: &str, to: &str) {
+        self.ndfa.add_transition(from.to_string(), "eps".to_string(), to.to_string());
+    }
+
+    // returns the (start, end) states of the fragment
+    fn build(&mut self, regex: &Regex) -> (String, String) {
+        match regex {
+            Regex::Empty => (self.new_state(), self.new_state()),
+            Regex::Epsilon => {
+                let (start, end) = (self.new_state(), self.new_state());
+                self.epsilon(&start, &end);
+                (start, end)
+            }
+            Regex::Symbol(symbol) => {
+                let (start, end) = (self.new_state(), self.new_state());
+                self.ndfa.add_transition(start.clone(), symbol.clone(), end.clone());
+                (start, end)
+            }
+            Regex::Union(left, right) => {
+                let start = self.new_state();
+                let (left_start, left_end) = self.build(left);
+                let (right_start, right_end) = self.build(right);
+                let end = self.new_state();
+                self.epsilon(&start, &left_start);
+                self.epsilon(&start, &right_start);
+                self.epsilon(&left_end, &end);
+                self.epsilon(&right_end, &end);
+                (start, end)
+            }
+            Regex::Concat(left, right) => {
+                let (left_start, left_end) = self.build(left);
+                let (right_start, right_end) = self.build(right);
+                self.epsilon(&left_end, &right_start);
+                (left_start, right_end)
+            }
+            Regex::Star(inner) | Regex::Plus(inner) | Regex::Optional(inner) => {
+                let start = self.new_state();
+                let (inner_start, inner_end) = self.build(inner);
+                let end = self.new_state();
+                self.epsilon(&start, &inner_start);
+                self.epsilon(&inner_end, &end);
+                // skipping the fragment
+                if !matches!(regex, Regex::Plus(_)) {
+                    self.epsilon(&start, &end);
+                }
+                // repeating the fragment
+                if !matches!(regex, Regex::Optional(_)) {
+                    self.epsilon(&inner_end, &inner_start);
+                }
+                (start, end)
+            }
+        }
+    }
+}
@@ -1,3 +1,56 @@
+use std::collections::HashSet;
+
 pub fn escape_dot_label(s: &str) -> String {
     s.replace('\"', "\\\"")
-}
\ No newline at end of file
+}
+
+// state names like (p,q) or {1,3} are not valid dot ids, those get quoted
+pub fn dot_id(s: &str) -> String {
+    let is_identifier = !s.is_empty()
+        && !s.starts_with(|c: char| c.is_ascii_digit())
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let is_numeral = !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+    if is_identifier || is_numeral {
+        s.to_string()
+    } else {
+        format!("\"{}\"", escape_dot_label(s))
+    }
+}
+
+// symbols are joined without spaces unless one of them is longer than a character
+pub fn format_word(word: &[String]) -> String {
+    if word.is_empty() {
+        "eps".to_string()
+    } else if word.iter().all(|symbol| symbol.chars().count() == 1) {
+        word.concat()
+    } else {
+        word.join(" ")
+    }
+}
+
+#[doc = r"* splits a word into alphabet symbols
+* whitespace separated words are taken symbol by symbol, otherwise the longest
+  matching alphabet symbol is taken at every position (multi character symbols),
+  characters not in the alphabet become single symbols
+* the longest match can go wrong when the alphabet is not prefix free, {a, ab, bc}
+  splits abc into ab c, NonDeterministicAutomaton::accepts tries every match instead"]
+pub fn split_word(word: &str, alphabet: &HashSet<String>) -> Vec<String> {
+    if word.contains(char::is_whitespace) {
+        return word.split_whitespace().map(String::from).collect();
+    }
+
+    let mut symbols: Vec<&String> = alphabet.iter().filter(|symbol| !symbol.is_empty()).collect();
+    symbols.sort_by_key(|symbol| std::cmp::Reverse(symbol.len()));
+
+    let mut result = Vec::new();
+    let mut rest = word;
+    while let Some(c) = rest.chars().next() {
+        let length = symbols.iter()
+            .find(|symbol| rest.starts_with(symbol.as_str()))
+            .map_or(c.len_utf8(), |symbol| symbol.len());
+        result.push(rest[..length].to_string());
+        rest = &rest[length..];
+    }
+    result
+}
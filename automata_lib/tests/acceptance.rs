@@ -0,0 +1,66 @@
+use automata_lib::NonDeterministicAutomaton;
+
+fn symbols(list: &[&str]) -> Vec<String> {
+    list.iter().map(|symbol| symbol.to_string()).collect()
+}
+
+fn nfa(start: &str, terminals: &[&str], transitions: &[(&str, &str, &str)]) -> NonDeterministicAutomaton {
+    let mut nfa = NonDeterministicAutomaton::new();
+    nfa.start_states.insert(start.to_string());
+    nfa.terminal_states = terminals.iter().map(|state| state.to_string()).collect();
+    for (from, symbol, to) in transitions {
+        nfa.states.insert(from.to_string());
+        nfa.states.insert(to.to_string());
+        nfa.alphabet.insert(symbol.to_string());
+        nfa.add_transition(from.to_string(), symbol.to_string(), to.to_string());
+    }
+    nfa
+}
+
+#[test]
+fn long_words_are_read_to_the_end() {
+    // an even number of a's
+    let even = nfa("e", &["e"], &[("e", "a", "o"), ("o", "a", "e")]);
+    assert!(even.accepts(&"a".repeat(5000)));
+    assert!(!even.accepts(&"a".repeat(5001)));
+    assert!(even.accepts_symbols(&vec!["a".to_string(); 5000]));
+}
+
+#[test]
+fn unicode_symbols() {
+    let alpha_beta = nfa("p", &["q"], &[("p", "α", "p"), ("p", "β", "q")]);
+    assert!(alpha_beta.accepts("ααβ") && alpha_beta.accepts("β"));
+    assert!(!alpha_beta.accepts("βα") && !alpha_beta.accepts("αa"));
+    assert!(alpha_beta.accepts("α α β"));
+}
+
+#[test]
+fn multi_character_symbols() {
+    let ab_c = nfa("p", &["r"], &[("p", "ab", "q"), ("q", "c", "r")]);
+    assert!(ab_c.accepts("abc") && ab_c.accepts("ab c"));
+    assert!(ab_c.accepts_symbols(&symbols(&["ab", "c"])));
+    assert!(!ab_c.accepts("ab") && !ab_c.accepts_symbols(&symbols(&["a", "b", "c"])));
+}
+
+#[test]
+fn alphabet_that_is_not_prefix_free() {
+    // the longest match would read abc as ab c
+    let mut a_bc = nfa("p", &["r"], &[("p", "a", "q"), ("q", "bc", "r")]);
+    a_bc.alphabet.insert("ab".to_string());
+    assert!(a_bc.accepts("abc"));
+    assert!(!a_bc.accepts("ab c") && !a_bc.accepts("abcbc"));
+
+    // both splits are tried at every position
+    a_bc.add_transition("p".to_string(), "ab".to_string(), "s".to_string());
+    a_bc.add_transition("s".to_string(), "c".to_string(), "r".to_string());
+    a_bc.alphabet.insert("c".to_string());
+    assert!(a_bc.accepts("abc") && a_bc.accepts("ab c"));
+}
+
+#[test]
+fn eps_loops_do_not_hang() {
+    let looping = nfa("p", &["r"], &[("p", "eps", "q"), ("q", "eps", "p"), ("q", "a", "r"), ("r", "eps", "r")]);
+    assert!(looping.accepts("a"));
+    assert!(!looping.accepts("") && !looping.accepts("aa"));
+    assert!(looping.accepts_symbols(&symbols(&["a"])));
+}
@@ -0,0 +1,90 @@
+use automata_lib::{Automaton, DeterministicAutomaton};
+use std::collections::HashMap;
+
+fn fel03() -> DeterministicAutomaton {
+    let mut dfa = DeterministicAutomaton::new();
+    dfa.build_from_file("../resources/fel03/form_I.B.3.txt").unwrap();
+    dfa
+}
+
+// the same automaton with every state renamed
+fn renamed(dfa: &DeterministicAutomaton, rename: impl Fn(&String) -> String) -> DeterministicAutomaton {
+    let mut renamed = DeterministicAutomaton::new();
+    renamed.alphabet = dfa.alphabet.clone();
+    renamed.states = dfa.states.iter().map(&rename).collect();
+    renamed.start_states = dfa.start_states.iter().map(&rename).collect();
+    renamed.terminal_states = dfa.terminal_states.iter().map(&rename).collect();
+    renamed.transitions = dfa.transitions.iter()
+        .map(|((from, symbol), to)| ((rename(from), symbol.clone()), rename(to)))
+        .collect();
+    renamed
+}
+
+// == on automata compares languages, this compares the structure
+fn assert_same_structure(left: &DeterministicAutomaton, right: &DeterministicAutomaton) {
+    assert_eq!(left.states, right.states);
+    assert_eq!(left.start_states, right.start_states);
+    assert_eq!(left.terminal_states, right.terminal_states);
+    assert_eq!(left.transitions, right.transitions);
+}
+
+#[test]
+fn canonical_form_numbers_states_in_bfs_order() {
+    let canonical = fel03().canonical_form();
+    // p, then q on a and r on b, then s
+    let expected = renamed(&fel03(), |state| HashMap::from([("p", "0"), ("q", "1"), ("r", "2"), ("s", "3")])[state.as_str()].to_string());
+    assert_same_structure(&canonical, &expected);
+}
+
+#[test]
+fn renamed_automata_are_isomorphic() {
+    let dfa = fel03();
+    let other = renamed(&dfa, |state| format!("{}'", state.to_uppercase()));
+
+    assert_same_structure(&dfa.canonical_form(), &other.canonical_form());
+    let bijection = dfa.is_isomorphic(&other).unwrap();
+    assert_eq!(bijection.len(), 4);
+    assert!(bijection.iter().all(|(state, image)| *image == format!("{}'", state.to_uppercase())));
+}
+
+#[test]
+fn unreachable_states_are_ignored() {
+    let dfa = fel03();
+    let mut with_unreachable = dfa.clone();
+    with_unreachable.states.insert("u".to_string());
+    with_unreachable.transitions.insert(("u".to_string(), "a".to_string()), "p".to_string());
+
+    assert_same_structure(&with_unreachable.canonical_form(), &dfa.canonical_form());
+    assert!(with_unreachable.is_isomorphic(&dfa).is_some());
+}
+
+#[test]
+fn different_structures_are_not_isomorphic() {
+    let dfa = fel03();
+
+    // same language, fewer states
+    let minimized = dfa.minimize_hopcroft().0;
+    assert!(minimized == dfa);
+    assert!(dfa.is_isomorphic(&minimized).is_none());
+
+    let mut other_terminal = dfa.clone();
+    other_terminal.terminal_states.insert("p".to_string());
+    assert!(dfa.is_isomorphic(&other_terminal).is_none());
+
+    let mut missing_transition = dfa.clone();
+    missing_transition.transitions.remove(&("s".to_string(), "a".to_string()));
+    assert!(dfa.is_isomorphic(&missing_transition).is_none());
+
+    let mut larger_alphabet = dfa.clone();
+    larger_alphabet.alphabet.insert("c".to_string());
+    assert!(dfa.is_isomorphic(&larger_alphabet).is_none());
+}
+
+#[test]
+fn minimal_automata_of_the_same_language_are_isomorphic() {
+    let dfa = fel03();
+    let hopcroft = dfa.minimize_hopcroft().0;
+    let brzozowski = dfa.minimize_brzozowski();
+    assert!(hopcroft.is_isomorphic(&brzozowski).is_some());
+    assert_same_structure(&hopcroft.canonical_form(), &brzozowski.canonical_form());
+}
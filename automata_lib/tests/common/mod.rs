@@ -0,0 +1,50 @@
+/*
+ * helpers shared by the integration tests, every test crate uses only some of them
+ */
+#![allow(dead_code)]
+
+use automata_lib::DeterministicAutomaton;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+
+// every word over the symbols up to the given length, shortest first
+pub fn words<S: AsRef<str>>(symbols: &[S], max_length: usize) -> Vec<String> {
+    let mut words = vec![String::new()];
+    let mut start = 0;
+    for _ in 0..max_length {
+        let end = words.len();
+        for index in start..end {
+            for symbol in symbols {
+                words.push(format!("{}{}", words[index], symbol.as_ref()));
+            }
+        }
+        start = end;
+    }
+    words
+}
+
+// a dfa over {a, b}
+pub fn dfa(states: &[&str], start: Option<&str>, terminals: &[&str], transitions: &[(&str, &str, &str)]) -> DeterministicAutomaton {
+    let mut dfa = DeterministicAutomaton::new();
+    dfa.states = states.iter().map(|state| state.to_string()).collect();
+    dfa.alphabet = HashSet::from(["a".to_string(), "b".to_string()]);
+    dfa.start_states.extend(start.map(String::from));
+    dfa.terminal_states = terminals.iter().map(|state| state.to_string()).collect();
+    for (from, symbol, to) in transitions {
+        dfa.transitions.insert((from.to_string(), symbol.to_string()), to.to_string());
+    }
+    dfa
+}
+
+// even number of a's
+pub fn even_a() -> DeterministicAutomaton {
+    dfa(&["e", "o"], Some("e"), &["e"], &[("e", "a", "o"), ("o", "a", "e"), ("e", "b", "e"), ("o", "b", "o")])
+}
+
+// writes the content to a temporary file named after the test
+pub fn write_file(name: &str, content: &str) -> String {
+    let path = env::temp_dir().join(format!("automata_lib_{}_{}.txt", name, std::process::id()));
+    fs::write(&path, content).unwrap();
+    path.to_str().unwrap().to_string()
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use automata_lib::DeterministicAutomaton;
+use common::{dfa, words};
+use std::collections::HashSet;
+
+fn check_complement(dfa: &DeterministicAutomaton) {
+    let complement = dfa.complement();
+    for word in words(&["a", "b"], 4) {
+        assert_ne!(complement.accepts(&word), dfa.accepts(&word), "word {:?}", word);
+    }
+}
+
+#[test]
+fn complement_of_an_incomplete_automaton() {
+    // starts and ends with b
+    check_complement(&dfa(
+        &["p", "q", "r"],
+        Some("p"),
+        &["r"],
+        &[("p", "b", "r"), ("q", "a", "q"), ("q", "b", "r"), ("r", "a", "q"), ("r", "b", "r")],
+    ));
+}
+
+#[test]
+fn complement_does_not_use_a_state_named_sink() {
+    let only_a = dfa(&["q", "sink"], Some("q"), &["sink"], &[("q", "a", "sink")]);
+    check_complement(&only_a);
+    assert_eq!(only_a.complement().start_states, HashSet::from(["q".to_string()]));
+}
+
+#[test]
+fn without_start_state_the_complement_is_universal() {
+    check_complement(&dfa(&[], None, &[], &[]));
+
+    // the start of the complement must not be the user state called sink
+    let no_start = dfa(&["sink"], None, &["sink"], &[("sink", "a", "sink")]);
+    check_complement(&no_start);
+    assert!(no_start.complement().is_universal());
+}
+
+#[test]
+fn complement_over_a_larger_alphabet() {
+    let mut only_a = dfa(&["q"], Some("q"), &["q"], &[("q", "a", "q")]);
+    only_a.alphabet = HashSet::from(["a".to_string()]);
+
+    let complement = only_a.complement_over(&HashSet::from(["b".to_string()]));
+    for word in words(&["a", "b"], 4) {
+        assert_eq!(complement.accepts(&word), word.contains('b'), "word {:?}", word);
+    }
+}
@@ -0,0 +1,38 @@
+use automata_lib::{Automaton, NonDeterministicAutomaton};
+use std::fs::read_to_string;
+
+fn check_word_list(automaton_file: &str, words_file: &str) {
+    let mut ndfa = NonDeterministicAutomaton::new();
+    ndfa.build_from_file(automaton_file).unwrap();
+    let mut eps_free = ndfa.clone();
+    eps_free.remove_epsilon_transitions();
+
+    assert!(eps_free.transitions.keys().all(|(_, symbol)| symbol != "eps"));
+    for word in read_to_string(words_file).unwrap().lines() {
+        assert_eq!(ndfa.accepts(word), eps_free.accepts(word), "word {:?}", word);
+    }
+}
+
+#[test]
+fn preserves_language_without_eps() {
+    check_word_list("../resources/1.A.4/form_I.A.4.txt", "../resources/1.A.4/form_I.A.4_szavak.txt");
+}
+
+#[test]
+fn preserves_language_with_eps() {
+    check_word_list("../resources/1.A.4/form_I.A.4_2.txt", "../resources/1.A.4/form_I.A.4_2_szavak.txt");
+}
+
+#[test]
+fn eps_reaching_terminal_makes_state_terminal() {
+    let mut ndfa = NonDeterministicAutomaton::new();
+    ndfa.build_from_file("../resources/1.A.4/form_I.A.4_2.txt").unwrap();
+    ndfa.add_transition("2".to_string(), "eps".to_string(), "4".to_string());
+    let mut eps_free = ndfa.clone();
+    eps_free.remove_epsilon_transitions();
+
+    assert!(eps_free.terminal_states.contains("2"));
+    assert!(eps_free.terminal_states.contains("3"));
+    assert!(eps_free.accepts("h"));
+    assert!(!eps_free.accepts(""));
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use automata_lib::AcceptingSide;
+use common::{dfa, even_a};
+
+fn word(symbols: &[&str]) -> Vec<String> {
+    symbols.iter().map(|symbol| symbol.to_string()).collect()
+}
+
+#[test]
+fn equivalent_automata_have_no_witness() {
+    // the same language with the even state split in two
+    let split = dfa(
+        &["e1", "e2", "o"],
+        Some("e1"),
+        &["e1", "e2"],
+        &[("e1", "a", "o"), ("e2", "a", "o"), ("o", "a", "e2"), ("e1", "b", "e2"), ("e2", "b", "e1"), ("o", "b", "o")],
+    );
+    assert_eq!(even_a().equivalence_witness(&split), None);
+    assert!(even_a() == split);
+}
+
+#[test]
+fn witness_is_the_shortest_distinguishing_word() {
+    // even number of a's and no b at all
+    let no_b = dfa(&["e", "o"], Some("e"), &["e"], &[("e", "a", "o"), ("o", "a", "e")]);
+
+    let witness = even_a().equivalence_witness(&no_b).unwrap();
+    assert_eq!(witness.word, word(&["b"]));
+    assert_eq!(witness.accepted_by, AcceptingSide::Left);
+
+    let witness = no_b.equivalence_witness(&even_a()).unwrap();
+    assert_eq!(witness.accepted_by, AcceptingSide::Right);
+    assert!(even_a() != no_b);
+}
+
+#[test]
+fn state_named_sink_is_not_the_sink() {
+    let with_sink = dfa(&["q", "sink"], Some("q"), &["sink"], &[("q", "a", "sink")]);
+    let renamed = dfa(&["q", "f"], Some("q"), &["f"], &[("q", "a", "f")]);
+    assert!(with_sink == renamed);
+
+    // missing transitions of with_sink must not lead to its terminal state
+    let a_or_b = dfa(&["q", "f"], Some("q"), &["f"], &[("q", "a", "f"), ("q", "b", "f")]);
+    let witness = with_sink.equivalence_witness(&a_or_b).unwrap();
+    assert_eq!(witness.word, word(&["b"]));
+    assert_eq!(witness.accepted_by, AcceptingSide::Right);
+}
+
+#[test]
+fn undeclared_transition_target_does_not_panic() {
+    // r is only mentioned as a transition target
+    let undeclared = dfa(&["q", "f"], Some("q"), &["f"], &[("q", "a", "r"), ("r", "b", "f")]);
+    let declared = dfa(&["q", "r", "f"], Some("q"), &["f"], &[("q", "a", "r"), ("r", "b", "f")]);
+    assert!(undeclared == declared);
+    assert_eq!(undeclared.equivalence_witness(&even_a()).unwrap().word, Vec::<String>::new());
+}
+
+#[test]
+fn missing_start_state_is_the_empty_language() {
+    let no_start = dfa(&["sink"], None, &["sink"], &[]);
+    let empty = dfa(&["q"], Some("q"), &[], &[]);
+    assert!(no_start == empty);
+    assert_eq!(no_start.equivalence_witness(&even_a()).unwrap().accepted_by, AcceptingSide::Right);
+}
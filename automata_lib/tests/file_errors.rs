@@ -0,0 +1,67 @@
+mod common;
+
+use automata_lib::{Automaton, AutomatonError, DeterministicAutomaton, NonDeterministicAutomaton, PushdownAutomaton};
+use common::write_file;
+use std::fs;
+
+fn load_dfa(name: &str, content: &str) -> AutomatonError {
+    let file_name = write_file(name, content);
+    let error = DeterministicAutomaton::new().build_from_file(&file_name).unwrap_err();
+    fs::remove_file(&file_name).unwrap();
+    assert_eq!(error.file(), file_name);
+    error
+}
+
+#[test]
+fn missing_section() {
+    let error = load_dfa("missing_section", "p q\na b\np\n");
+    assert!(matches!(&error, AutomatonError::MissingSection { section, .. } if section == "terminal states"), "{}", error);
+    assert_eq!((error.line(), error.column()), (Some(4), Some(1)));
+}
+
+#[test]
+fn unknown_state() {
+    let error = load_dfa("unknown_state", "p q\na b\np\nq\np a q\nq b  r\n");
+    assert!(matches!(&error, AutomatonError::UnknownState { state, .. } if state == "r"), "{}", error);
+    assert_eq!((error.line(), error.column()), (Some(6), Some(6)));
+
+    let error = load_dfa("unknown_terminal_state", "p q\na b\np\nq x\n");
+    assert!(matches!(&error, AutomatonError::UnknownState { state, .. } if state == "x"), "{}", error);
+    assert_eq!((error.line(), error.column()), (Some(4), Some(3)));
+}
+
+#[test]
+fn unknown_symbol() {
+    let error = load_dfa("unknown_symbol", "p q\na b\np\nq\np a q\n  q c p\n");
+    assert!(matches!(&error, AutomatonError::UnknownSymbol { symbol, .. } if symbol == "c"), "{}", error);
+    assert_eq!((error.line(), error.column()), (Some(6), Some(5)));
+    assert!(error.to_string().ends_with(":6:5: unknown symbol: c"), "{}", error);
+}
+
+#[test]
+fn duplicate_transition() {
+    let error = load_dfa("duplicate_transition", "p q\na b\np\nq\np a q\n\np a p\n");
+    assert!(matches!(&error, AutomatonError::DuplicateTransition { state, symbol, .. } if state == "p" && symbol == "a"), "{}", error);
+    assert_eq!((error.line(), error.column()), (Some(7), Some(1)));
+
+    // several targets are fine for an nfa
+    let file_name = write_file("nfa_duplicate_transition", "p q\na b\np\nq\np a q\n\np a p\n");
+    assert!(NonDeterministicAutomaton::new().build_from_file(&file_name).is_ok());
+    fs::remove_file(&file_name).unwrap();
+}
+
+#[test]
+fn missing_file_is_an_io_error() {
+    let error = DeterministicAutomaton::new().build_from_file("../resources/no_such_file.txt").unwrap_err();
+    assert!(matches!(error, AutomatonError::Io { .. }));
+    assert_eq!(error.location(), None);
+}
+
+#[test]
+fn invalid_acceptance_header() {
+    let file_name = write_file("invalid_acceptance", "acceptance: sometimes\np\na\nZ\np\nZ\np\n");
+    let error = PushdownAutomaton::new().build_from_file(&file_name).unwrap_err();
+    fs::remove_file(&file_name).unwrap();
+    assert!(matches!(error, AutomatonError::InvalidHeader { .. }), "{}", error);
+    assert_eq!(error.line(), Some(1));
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use automata_lib::{AutomatonError, ContextFreeGrammar, GrammarError, Production};
+use common::write_file;
+use std::collections::HashSet;
+use std::fs;
+
+fn load_error(name: &str, content: &str) -> AutomatonError {
+    let file_name = write_file(name, content);
+    let error = ContextFreeGrammar::new().build_from_file(&file_name).unwrap_err();
+    fs::remove_file(&file_name).unwrap();
+    error
+}
+
+fn symbols(list: &[&str]) -> Vec<String> {
+    list.iter().map(|symbol| symbol.to_string()).collect()
+}
+
+fn production(head: &str, body: &[&str]) -> Production {
+    Production { head: head.to_string(), body: symbols(body) }
+}
+
+fn anbn() -> ContextFreeGrammar {
+    let mut grammar = ContextFreeGrammar::new();
+    grammar.build_from_file("../resources/grammar/form_anbn.txt").unwrap();
+    grammar
+}
+
+#[test]
+fn parses_the_grammar_file() {
+    let grammar = anbn();
+    assert_eq!(grammar.nonterminals, symbols(&["S", "A", "B"]).into_iter().collect());
+    assert_eq!(grammar.terminals, symbols(&["a", "b"]).into_iter().collect());
+    assert_eq!(grammar.start_symbol, "S");
+    assert_eq!(
+        grammar.productions,
+        vec![
+            production("S", &["a", "S", "b"]),
+            production("S", &["A"]),
+            production("S", &[]),
+            production("A", &["a", "A"]),
+            production("B", &["b", "B"]),
+            production("B", &["b"]),
+        ]
+    );
+    assert_eq!(grammar.productions_of("S").count(), 3);
+    assert_eq!(grammar.validate(), Ok(()));
+}
+
+#[test]
+fn printed_grammar_reads_back() {
+    let grammar = anbn();
+    let text = grammar.to_string();
+    assert!(text.contains("S -> a S b | A | eps\n"), "{}", text);
+
+    let file_name = write_file("printed", &text);
+    let mut reread = ContextFreeGrammar::new();
+    reread.build_from_file(&file_name).unwrap();
+    fs::remove_file(&file_name).unwrap();
+    assert_eq!(reread.productions, grammar.productions);
+    assert_eq!(reread.nonterminals, grammar.nonterminals);
+}
+
+#[test]
+fn unproductive_and_unreachable_nonterminals() {
+    let grammar = anbn();
+    // A -> a A never ends, B does not appear in the rules of S
+    assert_eq!(grammar.unproductive_nonterminals(), symbols(&["A"]));
+    assert_eq!(grammar.unreachable_nonterminals(), symbols(&["B"]));
+
+    let mut grammar = ContextFreeGrammar::new();
+    grammar.nonterminals = symbols(&["S", "T"]).into_iter().collect();
+    grammar.terminals = symbols(&["a"]).into_iter().collect();
+    grammar.start_symbol = "S".to_string();
+    grammar.add_production("S", symbols(&["T", "T"]));
+    grammar.add_production("T", symbols(&["a"]));
+    grammar.add_production("T", symbols(&["a"]));
+    assert_eq!(grammar.productions.len(), 2);
+    assert!(grammar.unproductive_nonterminals().is_empty());
+    assert!(grammar.unreachable_nonterminals().is_empty());
+}
+
+#[test]
+fn error_locations() {
+    let error = load_error("unknown_symbol", "S\na b\nS\nS -> a S c\n");
+    assert!(matches!(&error, AutomatonError::UnknownSymbol { symbol, .. } if symbol == "c"), "{}", error);
+    assert_eq!((error.line(), error.column()), (Some(4), Some(10)));
+
+    let error = load_error("missing_arrow", "S\na b\nS\nS -> a\nS a b\n");
+    assert!(matches!(error, AutomatonError::InvalidProduction { .. }), "{}", error);
+    assert_eq!((error.line(), error.column()), (Some(5), Some(1)));
+
+    let error = load_error("eps_in_body", "S\na b\nS\nS -> a | b eps\n");
+    assert!(matches!(error, AutomatonError::InvalidProduction { .. }), "{}", error);
+    assert_eq!((error.line(), error.column()), (Some(4), Some(10)));
+
+    let error = load_error("unknown_head", "S\na b\nS\na -> b\n");
+    assert!(matches!(&error, AutomatonError::UnknownSymbol { symbol, .. } if symbol == "a"), "{}", error);
+    assert_eq!((error.line(), error.column()), (Some(4), Some(1)));
+}
+
+#[test]
+fn header_errors() {
+    let error = load_error("reserved", "S\na eps\nS\n");
+    assert!(matches!(error, AutomatonError::InvalidHeader { .. }), "{}", error);
+    assert_eq!((error.line(), error.column()), (Some(2), Some(3)));
+
+    let error = load_error("ambiguous", "S a\nb a\nS\n");
+    assert!(matches!(error, AutomatonError::InvalidHeader { .. }), "{}", error);
+    assert_eq!((error.line(), error.column()), (Some(2), Some(3)));
+
+    // the same messages as validate
+    let error = load_error("reserved_nonterminal", "S ->\na\nS\n");
+    assert_eq!((error.line(), error.column()), (Some(1), Some(3)));
+    assert!(error.to_string().contains(&GrammarError::ReservedSymbol("->".to_string()).to_string()), "{}", error);
+    assert!(load_error("ambiguous_message", "S a\nb a\nS\n").to_string().contains(&GrammarError::AmbiguousSymbol("a".to_string()).to_string()));
+
+    let error = load_error("start_not_nonterminal", "S\na\n a\n");
+    assert!(matches!(&error, AutomatonError::UnknownSymbol { symbol, .. } if symbol == "a"), "{}", error);
+    assert_eq!((error.line(), error.column()), (Some(3), Some(2)));
+
+    let error = load_error("missing_start", "S\na\n");
+    assert!(matches!(&error, AutomatonError::MissingSection { section, .. } if section == "start symbol"), "{}", error);
+    assert_eq!(error.line(), Some(3));
+}
+
+#[test]
+fn validate_reports_malformed_grammars() {
+    let mut grammar = ContextFreeGrammar::new();
+    grammar.nonterminals = HashSet::from(["S".to_string()]);
+    grammar.terminals = HashSet::from(["a".to_string()]);
+    grammar.start_symbol = "T".to_string();
+    assert_eq!(grammar.validate(), Err(GrammarError::UnknownStartSymbol("T".to_string())));
+
+    grammar.start_symbol = "S".to_string();
+    grammar.add_production("S", symbols(&["a", "b"]));
+    assert_eq!(grammar.validate(), Err(GrammarError::UnknownSymbol("b".to_string())));
+
+    grammar.productions.clear();
+    grammar.add_production("a", symbols(&["S"]));
+    assert_eq!(grammar.validate(), Err(GrammarError::NotANonterminal("a".to_string())));
+
+    grammar.terminals.insert("S".to_string());
+    assert_eq!(grammar.validate(), Err(GrammarError::AmbiguousSymbol("S".to_string())));
+
+    grammar.terminals.insert("eps".to_string());
+    assert_eq!(grammar.validate(), Err(GrammarError::ReservedSymbol("eps".to_string())));
+}
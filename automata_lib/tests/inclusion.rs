@@ -0,0 +1,45 @@
+mod common;
+
+use automata_lib::{NonDeterministicAutomaton, Regex};
+use common::words;
+use std::collections::HashSet;
+
+fn nfa(pattern: &str) -> NonDeterministicAutomaton {
+    let ab = HashSet::from(["a".to_string(), "b".to_string()]);
+    Regex::parse(pattern, &ab).unwrap().to_nondeterministic(&ab)
+}
+
+// the witness is in L(left) \ L(right) and no shorter word is
+fn check_shortest_witness(left: &str, right: &str, expected: &[&str]) {
+    let (left, right) = (nfa(left), nfa(right));
+    let witness = left.check_inclusion(&right).unwrap_err();
+    assert_eq!(witness, expected.iter().map(|symbol| symbol.to_string()).collect::<Vec<_>>());
+
+    for word in words(&["a", "b"], witness.len() - 1) {
+        assert!(!left.accepts(&word) || right.accepts(&word), "shorter witness {:?}", word);
+    }
+    assert_eq!(left.to_deterministic().check_inclusion(&right.to_deterministic()).unwrap_err().len(), witness.len());
+}
+
+#[test]
+fn included_languages() {
+    for (left, right) in [("(ab)*", "(a|b)*"), ("a+", "a*"), ("a(a|b)*b", "a(a|b)*|b*"), ("∅", "a"), ("eps", "a*b*")] {
+        assert_eq!(nfa(left).check_inclusion(&nfa(right)), Ok(()), "{} ⊆ {}", left, right);
+        assert!(nfa(left).is_subset_of(&nfa(right)));
+    }
+}
+
+#[test]
+fn not_included_languages() {
+    assert!(!nfa("a*").is_subset_of(&nfa("a+")));
+    assert_eq!(nfa("a*").check_inclusion(&nfa("a+")), Err(Vec::new()));
+    assert!(!nfa("(a|b)*").is_subset_of(&nfa("(ab)*")));
+}
+
+#[test]
+fn witness_is_shortest() {
+    check_shortest_witness("a*b", "a*", &["b"]);
+    check_shortest_witness("a*", "eps|a|aa|aaaaa*", &["a", "a", "a"]);
+    check_shortest_witness("(a|b)*", "(a|b)*a|eps|(a|b)*bb", &["b"]);
+    check_shortest_witness("(a|b)*abb", "(a|b)*aabb|bbb*", &["a", "b", "b"]);
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use automata_lib::{DeterministicAutomaton, LanguageSize, NonDeterministicAutomaton, Regex};
+use num_bigint::BigUint;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::{HashMap, HashSet};
+
+fn ab() -> HashSet<String> {
+    HashSet::from(["a".to_string(), "b".to_string()])
+}
+
+fn nfa(pattern: &str) -> NonDeterministicAutomaton {
+    Regex::parse(pattern, &ab()).unwrap().to_nondeterministic(&ab())
+}
+
+fn dfa(pattern: &str) -> DeterministicAutomaton {
+    nfa(pattern).to_deterministic()
+}
+
+// 0 -a,b-> 1 -a,b-> ... -a,b-> length, terminal states as given
+fn chain(length: usize, terminal: impl Fn(usize) -> bool) -> DeterministicAutomaton {
+    let mut dfa = DeterministicAutomaton::new();
+    dfa.alphabet = ab();
+    dfa.start_states.insert("0".to_string());
+    for state in 0..=length {
+        dfa.states.insert(state.to_string());
+        if terminal(state) {
+            dfa.terminal_states.insert(state.to_string());
+        }
+        if state < length {
+            for symbol in ["a", "b"] {
+                dfa.transitions.insert((state.to_string(), symbol.to_string()), (state + 1).to_string());
+            }
+        }
+    }
+    dfa
+}
+
+#[test]
+fn finite_languages_are_counted() {
+    for (pattern, size) in [("eps", 1u32), ("a|b", 2), ("(a|b)(a|b)?", 6), ("ab|ab|ba", 2)] {
+        assert!(dfa(pattern).is_finite(), "{}", pattern);
+        assert!(nfa(pattern).is_finite(), "{}", pattern);
+        assert_eq!(dfa(pattern).language_size(), LanguageSize::Finite(BigUint::from(size)), "{}", pattern);
+        assert_eq!(nfa(pattern).language_size(), LanguageSize::Finite(BigUint::from(size)), "{}", pattern);
+    }
+}
+
+#[test]
+fn cycles_on_useful_states_make_the_language_infinite() {
+    for pattern in ["a*", "(ab)+b", "a(a|b)*"] {
+        assert!(!dfa(pattern).is_finite(), "{}", pattern);
+        assert!(!nfa(pattern).is_finite(), "{}", pattern);
+        assert_eq!(nfa(pattern).language_size(), LanguageSize::Infinite, "{}", pattern);
+    }
+
+    // a cycle on a dead state does not count
+    let mut dead_cycle = dfa("ab");
+    let dead = "dead".to_string();
+    dead_cycle.states.insert(dead.clone());
+    dead_cycle.transitions.insert((dead.clone(), "a".to_string()), dead.clone());
+    let start = dead_cycle.start_states.iter().next().unwrap().clone();
+    dead_cycle.transitions.insert((start, "b".to_string()), dead);
+    assert_eq!(dead_cycle.language_size(), LanguageSize::Finite(BigUint::from(1u8)));
+}
+
+#[test]
+fn empty_and_universal_languages() {
+    for pattern in ["∅", "∅a", "(∅b)*∅"] {
+        assert!(dfa(pattern).is_empty(), "{}", pattern);
+        assert!(nfa(pattern).is_empty(), "{}", pattern);
+    }
+    assert!(!dfa("eps").is_empty() && !nfa("a*").is_empty());
+    assert_eq!(dfa("∅").language_size(), LanguageSize::Finite(BigUint::from(0u8)));
+
+    for pattern in ["(a|b)*", "a*(b(a|b)*)?", "(a*b*)*"] {
+        assert!(dfa(pattern).is_universal(), "{}", pattern);
+        assert!(nfa(pattern).is_universal(), "{}", pattern);
+    }
+    for pattern in ["a*", "(a|b)+", "∅"] {
+        assert!(!dfa(pattern).is_universal(), "{}", pattern);
+    }
+}
+
+#[test]
+fn large_counts_do_not_overflow() {
+    // every word of length 100: 2^100 words
+    let exactly = chain(100, |state| state == 100);
+    assert_eq!(exactly.language_size(), LanguageSize::Finite(BigUint::from(2u8).pow(100)));
+
+    // every word up to length 200: 2^201 - 1 words
+    let up_to = chain(200, |_| true);
+    assert_eq!(up_to.language_size(), LanguageSize::Finite(BigUint::from(2u8).pow(201) - 1u8));
+}
+
+fn words(list: &[&str]) -> Vec<Vec<String>> {
+    list.iter().map(|word| word.chars().map(String::from).collect()).collect()
+}
+
+#[test]
+fn accepted_words_come_in_shortlex_order() {
+    let all: Vec<Vec<String>> = dfa("(a|b)*").accepted_words(None).take(7).collect();
+    assert_eq!(all, words(&["", "a", "b", "aa", "ab", "ba", "bb"]));
+
+    let up_to_three: Vec<Vec<String>> = nfa("a*b").accepted_words(Some(3)).collect();
+    assert_eq!(up_to_three, words(&["b", "ab", "aab"]));
+
+    let finite: Vec<Vec<String>> = dfa("ba|a|ab|bb").accepted_words(None).collect();
+    assert_eq!(finite, words(&["a", "ab", "ba", "bb"]));
+}
+
+#[test]
+fn accepted_words_end_on_dead_cycles() {
+    // the dead state loops forever, but it is trimmed away before the search
+    let mut dead_cycle = chain(2, |state| state == 1);
+    dead_cycle.transitions.insert(("2".to_string(), "a".to_string()), "2".to_string());
+    assert_eq!(dead_cycle.accepted_words(None).collect::<Vec<_>>(), words(&["a", "b"]));
+
+    dead_cycle.terminal_states.clear();
+    assert_eq!(dead_cycle.accepted_words(None).next(), None);
+    assert_eq!(dfa("∅").accepted_words(None).next(), None);
+}
+
+#[test]
+fn counts_match_brute_force() {
+    for pattern in ["(a|b)*abb", "a*b*", "(ab|ba)*", "a(a|b)*a|b", "∅"] {
+        let dfa = dfa(pattern);
+        let counts = dfa.count_words_by_length(8);
+        for (length, count) in counts.iter().enumerate() {
+            let expected = common::words(&["a", "b"], length).iter().filter(|word| word.len() == length && dfa.accepts(word)).count();
+            assert_eq!(*count, BigUint::from(expected), "{} at length {}", pattern, length);
+        }
+        assert_eq!(dfa.count_words_of_length(8), counts[8]);
+    }
+}
+
+#[test]
+fn seeded_sampling_is_reproducible_and_uniform() {
+    let dfa = dfa("a(a|b)(a|b)|bbb");
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for _ in 0..5000 {
+        let word = dfa.random_word(3, &mut rng).unwrap().concat();
+        assert!(dfa.accepts(&word), "{}", word);
+        *seen.entry(word).or_default() += 1;
+    }
+
+    // 5 words, about 1000 samples each
+    assert_eq!(seen.len(), 5);
+    assert!(seen.values().all(|count| (850..1150).contains(count)), "{:?}", seen);
+
+    assert_eq!(dfa.random_words(3, 20, 42), dfa.random_words(3, 20, 42));
+    assert_eq!(dfa.random_words(3, 20, 42).len(), 20);
+}
+
+#[test]
+fn sampling_without_words_of_the_length() {
+    let three = dfa("a(a|b)(a|b)|bbb");
+    let mut rng = StdRng::seed_from_u64(7);
+    assert_eq!(three.random_word(2, &mut rng), None);
+    assert!(three.random_words(4, 10, 1).is_empty());
+    assert_eq!(dfa("∅").random_word(0, &mut rng), None);
+    assert_eq!(dfa("eps").random_word(0, &mut rng), Some(Vec::new()));
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use automata_lib::{Automaton, DeterministicAutomaton, MergedBlock, MinimizationAlgorithm};
+use common::dfa;
+use std::collections::HashSet;
+
+const ALGORITHMS: [MinimizationAlgorithm; 4] = [
+    MinimizationAlgorithm::Partition,
+    MinimizationAlgorithm::Hopcroft,
+    MinimizationAlgorithm::Moore,
+    MinimizationAlgorithm::Brzozowski,
+];
+
+fn check_algorithms_agree(file_name: &str, expected_states: usize) {
+    let mut dfa = DeterministicAutomaton::new();
+    dfa.build_from_file(file_name).unwrap();
+
+    for algorithm in ALGORITHMS {
+        let minimized = dfa.minimize_with(algorithm);
+        assert_eq!(minimized.states.len(), expected_states, "{:?}", algorithm);
+        assert!(minimized == dfa, "{:?} changed the language", algorithm);
+    }
+}
+
+#[test]
+fn algorithms_agree_on_fel03_a() {
+    check_algorithms_agree("../resources/fel03/form_I.B.3.txt", 3);
+}
+
+#[test]
+fn algorithms_agree_on_fel03_b() {
+    check_algorithms_agree("../resources/fel03/form_I.B.3_b.txt", 4);
+}
+
+#[test]
+fn hopcroft_maps_merged_states_to_one_block() {
+    let mut dfa = DeterministicAutomaton::new();
+    dfa.build_from_file("../resources/fel03/form_I.B.3.txt").unwrap();
+    let (_, mapping) = dfa.minimize_hopcroft();
+
+    assert_eq!(mapping.len(), 4);
+    assert_eq!(mapping["q"], mapping["s"]);
+    assert_ne!(mapping["p"], mapping["r"]);
+}
+
+#[test]
+fn hopcroft_is_idempotent() {
+    let mut dfa = DeterministicAutomaton::new();
+    dfa.build_from_file("../resources/fel03/form_I.B.3_b.txt").unwrap();
+    let (minimized, mapping) = dfa.minimize_hopcroft();
+    let (again, again_mapping) = minimized.minimize_hopcroft();
+
+    assert_eq!(again.states, minimized.states);
+    assert!(again_mapping.iter().all(|(state, block)| state == block));
+    assert!(mapping.values().all(|block| minimized.states.contains(block)));
+}
+
+#[test]
+fn hopcroft_keeps_missing_transitions_missing() {
+    // q and r only loop on a, b is missing from both
+    let dfa = dfa(
+        &["p", "q", "r"],
+        Some("p"),
+        &["q", "r"],
+        &[("p", "a", "q"), ("p", "b", "r"), ("q", "a", "q"), ("r", "a", "r")],
+    );
+    let (minimized, mapping) = dfa.minimize_hopcroft();
+
+    assert_eq!(minimized.states, HashSet::from(["p".to_string(), "q".to_string()]));
+    assert_eq!(mapping["r"], "q");
+    assert!(!minimized.transitions.contains_key(&("q".to_string(), "b".to_string())));
+    assert!(minimized == dfa);
+}
+
+#[test]
+fn hopcroft_maps_only_reachable_states() {
+    let (minimized, mapping) = dfa(&["p", "u"], Some("p"), &["p", "u"], &[("p", "a", "p"), ("u", "a", "p")]).minimize_hopcroft();
+    assert!(!mapping.contains_key("u"));
+    assert_eq!(minimized.states, HashSet::from(["p".to_string()]));
+
+    let (empty, mapping) = dfa(&["p"], None, &["p"], &[]).minimize_hopcroft();
+    assert!(empty.states.is_empty() && empty.start_states.is_empty() && mapping.is_empty());
+}
+
+#[test]
+fn algorithms_return_the_same_normal_form() {
+    // d is dead, u is unreachable, q and r are equivalent
+    let dfa = dfa(
+        &["p", "q", "r", "d", "u"],
+        Some("p"),
+        &["q", "r", "u"],
+        &[("p", "a", "q"), ("p", "b", "r"), ("q", "a", "q"), ("q", "b", "d"), ("r", "a", "r"), ("r", "b", "d"), ("d", "a", "d"), ("d", "b", "d"), ("u", "a", "p")],
+    );
+
+    let expected = dfa.minimize_with(MinimizationAlgorithm::Hopcroft);
+    assert_eq!(expected.states, HashSet::from(["p".to_string(), "q".to_string()]));
+    assert_eq!(expected.transitions.len(), 3);
+    for algorithm in ALGORITHMS {
+        let minimized = dfa.minimize_with(algorithm);
+        assert!(minimized.is_isomorphic(&expected).is_some(), "{:?}: {:?}", algorithm, minimized);
+        assert!(minimized == dfa, "{:?} changed the language", algorithm);
+    }
+}
+
+#[test]
+fn dead_states_of_a_complete_automaton_are_dropped() {
+    // there is no missing transition, so no virtual sink, d and e are both dead
+    let complete = dfa(
+        &["p", "q", "d", "e"],
+        Some("p"),
+        &["q"],
+        &[("p", "a", "q"), ("p", "b", "d"), ("q", "a", "q"), ("q", "b", "e"), ("d", "a", "e"), ("d", "b", "d"), ("e", "a", "d"), ("e", "b", "e")],
+    );
+    for algorithm in ALGORITHMS {
+        let minimized = complete.minimize_with(algorithm);
+        assert_eq!(minimized.states.len(), 2, "{:?}", algorithm);
+        assert_eq!(minimized.transitions.len(), 2, "{:?}", algorithm);
+        assert!(minimized == complete, "{:?} changed the language", algorithm);
+    }
+    let (_, mapping) = complete.minimize_hopcroft();
+    assert!(!mapping.contains_key("d") && !mapping.contains_key("e"));
+}
+
+#[test]
+fn algorithms_agree_on_the_empty_language() {
+    let dead_start = dfa(&["p", "q"], Some("p"), &["q"], &[("p", "a", "p")]);
+    for algorithm in ALGORITHMS {
+        let minimized = dead_start.minimize_with(algorithm);
+        assert_eq!(minimized.states.len(), 1, "{:?}", algorithm);
+        assert!(minimized.terminal_states.is_empty() && minimized.transitions.is_empty(), "{:?}", algorithm);
+        assert_eq!(minimized.start_states, minimized.states, "{:?}", algorithm);
+
+        assert!(dfa(&["p"], None, &["p"], &[]).minimize_with(algorithm).states.is_empty(), "{:?}", algorithm);
+    }
+}
+
+#[test]
+fn report_lists_removed_and_merged_states() {
+    let dfa = dfa(
+        &["p", "q", "r", "d", "u"],
+        Some("p"),
+        &["q", "r", "u"],
+        &[("p", "a", "q"), ("p", "b", "r"), ("q", "a", "q"), ("q", "b", "d"), ("r", "a", "r"), ("r", "b", "d"), ("d", "a", "d"), ("d", "b", "d"), ("u", "a", "p")],
+    );
+    let report = dfa.minimization_report();
+
+    assert_eq!(report.removed_unreachable, vec!["u".to_string()]);
+    assert_eq!(report.removed_dead, vec!["d".to_string()]);
+    assert_eq!(report.merged_blocks, vec![MergedBlock { representative: "q".to_string(), states: vec!["q".to_string(), "r".to_string()] }]);
+    assert!(!report.is_minimal());
+    assert!(report.automaton == dfa);
+    assert_eq!(report.automaton.states, HashSet::from(["p".to_string(), "q".to_string()]));
+}
+
+#[test]
+fn report_of_a_minimal_automaton_is_empty() {
+    let mut fel03 = DeterministicAutomaton::new();
+    fel03.build_from_file("../resources/fel03/form_I.B.3.txt").unwrap();
+    let report = fel03.is_minimized();
+    assert!(!report.is_minimal());
+    assert_eq!(report.merged_blocks.len(), 1);
+
+    let report = report.automaton.minimization_report();
+    assert!(report.is_minimal());
+    assert!(report.removed_unreachable.is_empty() && report.removed_dead.is_empty() && report.merged_blocks.is_empty());
+
+    // the start state of the empty language is kept, so it is not reported as dead
+    let dead_start = dfa(&["p"], Some("p"), &[], &[("p", "a", "p")]);
+    assert!(dead_start.minimization_report().removed_dead.is_empty());
+}
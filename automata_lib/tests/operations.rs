@@ -0,0 +1,64 @@
+mod common;
+
+use automata_lib::{NonDeterministicAutomaton, Regex};
+use common::words;
+use std::collections::HashSet;
+
+fn nfa(pattern: &str) -> NonDeterministicAutomaton {
+    let ab = HashSet::from(["a".to_string(), "b".to_string()]);
+    Regex::parse(pattern, &ab).unwrap().to_nondeterministic(&ab)
+}
+
+// the operation has to accept the same words as the regex
+fn check_language(result: &NonDeterministicAutomaton, pattern: &str) {
+    let expected = nfa(pattern);
+    for word in words(&["a", "b"], 5) {
+        assert_eq!(result.accepts(&word), expected.accepts(&word), "{} on {:?}", pattern, word);
+    }
+}
+
+#[test]
+fn union_and_concatenation() {
+    check_language(&nfa("ab").union(&nfa("b*")), "ab|b*");
+    check_language(&nfa("ab").concatenation(&nfa("b*")), "abb*");
+    check_language(&nfa("a|eps").concatenation(&nfa("ba")), "(a|eps)ba");
+}
+
+#[test]
+fn repetitions() {
+    check_language(&nfa("ab").kleene_star(), "(ab)*");
+    check_language(&nfa("ab").kleene_plus(), "(ab)+");
+    check_language(&nfa("a|bb").optional(), "(a|bb)?");
+    check_language(&nfa("a*b").kleene_star().concatenation(&nfa("a")), "(a*b)*a");
+}
+
+#[test]
+fn operands_with_the_same_state_names() {
+    // both sides come from the same construction, so their state names clash
+    let ab = nfa("ab");
+    let union = ab.union(&ab);
+    assert_eq!(union.states.len(), 2 * ab.states.len());
+    check_language(&union, "ab");
+    check_language(&ab.concatenation(&ab), "abab");
+    check_language(&ab.concatenation(&ab).kleene_star(), "(abab)*");
+}
+
+#[test]
+fn new_start_state_avoids_used_names() {
+    // s and s1 are taken, s2 is only used as a transition target
+    let mut nfa = NonDeterministicAutomaton::new();
+    nfa.states = HashSet::from(["s".to_string(), "s1".to_string()]);
+    nfa.alphabet = HashSet::from(["a".to_string(), "b".to_string()]);
+    nfa.start_states.insert("s".to_string());
+    nfa.terminal_states.insert("s1".to_string());
+    nfa.add_transition("s".to_string(), "a".to_string(), "s2".to_string());
+    nfa.add_transition("s2".to_string(), "b".to_string(), "s1".to_string());
+
+    let star = nfa.kleene_star();
+    assert_eq!(star.start_states, HashSet::from(["s3".to_string()]));
+    check_language(&star, "(ab)*");
+
+    let optional = nfa.optional();
+    assert!(optional.start_states.contains("s3"));
+    check_language(&optional, "(ab)?");
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use automata_lib::DeterministicAutomaton;
+use common::{dfa, even_a, words};
+
+fn everything() -> DeterministicAutomaton {
+    dfa(&["u"], Some("u"), &["u"], &[("u", "a", "u"), ("u", "b", "u")])
+}
+
+// starts and ends with b, incomplete: there is no transition on a from the start state
+fn starts_and_ends_with_b() -> DeterministicAutomaton {
+    dfa(&["p", "q", "r"], Some("p"), &["r"], &[("p", "b", "r"), ("q", "a", "q"), ("q", "b", "r"), ("r", "a", "q"), ("r", "b", "r")])
+}
+
+type BooleanOperation = fn(bool, bool) -> bool;
+
+#[test]
+fn operations_match_the_boolean_combination() {
+    let left = even_a();
+    let right = starts_and_ends_with_b();
+    let checks: [(DeterministicAutomaton, BooleanOperation); 4] = [
+        (left.intersection(&right), |l, r| l && r),
+        (left.union(&right), |l, r| l || r),
+        (left.difference(&right), |l, r| l && !r),
+        (left.symmetric_difference(&right), |l, r| l != r),
+    ];
+
+    for (product, operation) in checks {
+        for word in words(&["a", "b"], 4) {
+            assert_eq!(product.accepts(&word), operation(left.accepts(&word), right.accepts(&word)), "word {:?}", word);
+        }
+    }
+}
+
+#[test]
+fn product_does_not_use_a_state_named_sink() {
+    // accepts only "a", the terminal state happens to be called sink
+    let only_a = dfa(&["q", "sink"], Some("q"), &["sink"], &[("q", "a", "sink")]);
+    let intersection = only_a.intersection(&everything());
+    for word in words(&["a", "b"], 4) {
+        assert_eq!(intersection.accepts(&word), word == "a", "word {:?}", word);
+    }
+    assert!(only_a.union(&everything()).accepts("bb"));
+}
+
+#[test]
+fn product_with_an_undeclared_transition_target() {
+    // r is only mentioned as a transition target
+    let ab = dfa(&["q", "f"], Some("q"), &["f"], &[("q", "a", "r"), ("r", "b", "f")]);
+    let intersection = ab.intersection(&everything());
+    for word in words(&["a", "b"], 4) {
+        assert_eq!(intersection.accepts(&word), word == "ab", "word {:?}", word);
+    }
+
+    let mut trimmed = ab.clone();
+    trimmed.trim();
+    assert!(trimmed.states.contains("r"));
+    assert!(trimmed.is_finite());
+}
+
+#[test]
+fn product_with_a_missing_start_state() {
+    let mut empty = even_a();
+    empty.start_states.clear();
+
+    for word in words(&["a", "b"], 4) {
+        assert!(!empty.intersection(&everything()).accepts(&word), "word {:?}", word);
+        assert!(empty.union(&everything()).accepts(&word), "word {:?}", word);
+        assert!(everything().difference(&empty).accepts(&word), "word {:?}", word);
+        assert!(empty.symmetric_difference(&everything()).accepts(&word), "word {:?}", word);
+        assert_eq!(even_a().union(&empty).accepts(&word), even_a().accepts(&word), "word {:?}", word);
+    }
+    assert!(empty.union(&empty.complement()).is_universal());
+}
+
+#[test]
+fn pair_names_do_not_collide() {
+    // ("a,b", "c") and ("a", "b,c") would both be called (a,b,c)
+    let left = dfa(&["a", "a,b"], Some("a"), &["a,b"], &[("a", "a", "a,b"), ("a,b", "a", "a,b"), ("a", "b", "a"), ("a,b", "b", "a")]);
+    let right = dfa(&["c", "b,c"], Some("b,c"), &["c"], &[("b,c", "a", "c"), ("c", "a", "c"), ("b,c", "b", "b,c"), ("c", "b", "b,c")]);
+
+    let intersection = left.intersection(&right);
+    for word in words(&["a", "b"], 4) {
+        assert_eq!(intersection.accepts(&word), left.accepts(&word) && right.accepts(&word), "word {:?}", word);
+    }
+    let union = left.union(&right);
+    assert!(union.states.contains("(a,b,c)") && union.states.contains("(a,b,c)'"));
+}
@@ -0,0 +1,289 @@
+mod common;
+
+use automata_lib::{AcceptanceMode, AcceptanceResult, Automaton, PushdownAutomaton, PushdownAutomatonBuilder, PushdownBuildError, PushdownConfiguration, DEFAULT_MAX_CONFIGURATIONS, DEFAULT_MAX_STACK_HEIGHT};
+use std::fs::read_to_string;
+
+fn load(file_name: &str) -> PushdownAutomaton {
+    let mut pda = PushdownAutomaton::new();
+    pda.build_from_file(file_name).unwrap();
+    pda
+}
+
+// the words of the word list and every word over the input symbols up to length 5
+fn test_words(pda: &PushdownAutomaton, words_file: &str) -> Vec<String> {
+    let mut symbols: Vec<&String> = pda.input_symbols().iter().collect();
+    symbols.sort();
+    let mut words: Vec<String> = read_to_string(words_file).unwrap().lines().map(String::from).collect();
+    words.extend(common::words(&symbols, 5));
+    words
+}
+
+fn check_conversions(pda: &PushdownAutomaton, words: &[String]) {
+    let final_state = pda.to_final_state_acceptance();
+    let empty_stack = pda.to_empty_stack_acceptance();
+    assert_eq!(final_state.acceptance_mode(), AcceptanceMode::FinalState);
+    assert_eq!(empty_stack.acceptance_mode(), AcceptanceMode::EmptyStack);
+    assert!(empty_stack.terminal_states().is_empty());
+
+    for word in words {
+        let accepted = pda.accepts(word);
+        assert_eq!(final_state.accepts(word), accepted, "{:?} to final state, word {:?}", pda.acceptance_mode(), word);
+        assert_eq!(empty_stack.accepts(word), accepted, "{:?} to empty stack, word {:?}", pda.acceptance_mode(), word);
+        assert_eq!(final_state.to_empty_stack_acceptance().accepts(word), accepted, "round trip, word {:?}", word);
+    }
+}
+
+#[test]
+fn conversions_keep_the_language_of_fel02() {
+    let pda = load("../resources/fel02/form_I.B.2.txt");
+    assert_eq!(pda.acceptance_mode(), AcceptanceMode::FinalState);
+    let words = test_words(&pda, "../resources/fel02/form_I.B.2_szavak.txt");
+    assert!(words.iter().any(|word| pda.accepts(word)) && words.iter().any(|word| !pda.accepts(word)));
+    check_conversions(&pda, &words);
+}
+
+#[test]
+fn conversions_keep_the_language_of_fel02_b() {
+    let pda = load("../resources/fel02/form_I.B.2_b.txt");
+    assert_eq!(pda.acceptance_mode(), AcceptanceMode::EmptyStack);
+    let words = test_words(&pda, "../resources/fel02/form_I.B.2_b_szavak.txt");
+    assert!(words.iter().any(|word| pda.accepts(word)) && words.iter().any(|word| !pda.accepts(word)));
+    check_conversions(&pda, &words);
+}
+
+#[test]
+fn conversions_from_both_modes() {
+    for file_name in ["../resources/fel02/form_I.B.2.txt", "../resources/fel02/form_I.B.2_b.txt"] {
+        let mut pda = load(file_name);
+        pda.set_acceptance_mode(AcceptanceMode::Both);
+        let words = test_words(&pda, &file_name.replace(".txt", "_szavak.txt"));
+        check_conversions(&pda, &words);
+    }
+}
+
+#[test]
+fn acceptance_mode_names() {
+    for mode in [AcceptanceMode::FinalState, AcceptanceMode::EmptyStack, AcceptanceMode::Both] {
+        assert_eq!(AcceptanceMode::from_name(mode.name()), Some(mode));
+    }
+    assert_eq!(AcceptanceMode::from_name("final"), None);
+    assert_eq!(PushdownAutomaton::with_acceptance_mode(AcceptanceMode::Both).acceptance_mode(), AcceptanceMode::Both);
+}
+
+#[test]
+fn decide_accepts_and_rejects_fel02_words() {
+    let pda = load("../resources/fel02/form_I.B.2.txt");
+    for word in ["", "aabb", "aaab", "aaaabbbb"] {
+        assert_eq!(pda.decide(word), AcceptanceResult::Accepted, "{}", word);
+    }
+    for word in ["b", "abab", "aabbb", "ac"] {
+        assert_eq!(pda.decide(word), AcceptanceResult::Rejected, "{}", word);
+    }
+}
+
+// guesses the number of a's with an eps loop, then reads them
+fn guess_a_count() -> PushdownAutomaton {
+    PushdownAutomaton::builder()
+        .add_state("q").add_state("r").add_state("f")
+        .add_input_symbol("a")
+        .add_stack_symbol("Z").add_stack_symbol("A")
+        .start_state("q").stack_start_symbol("Z").add_terminal_state("f")
+        .add_transition("q", "eps", "Z", "A Z", "q")
+        .add_transition("q", "eps", "A", "A A", "q")
+        .add_transition("q", "eps", "A", "A", "r")
+        .add_transition("r", "a", "A", "eps", "r")
+        .add_transition("r", "eps", "Z", "Z", "f")
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn stack_height_bound_makes_the_result_undecided() {
+    let mut pda = guess_a_count();
+    assert_eq!(pda.decide("aaaaa"), AcceptanceResult::Accepted);
+    // the guessing loop can always push one more A
+    assert_eq!(pda.decide(""), AcceptanceResult::Undecided);
+
+    pda.set_max_stack_height(4);
+    assert_eq!(pda.decide("aaa"), AcceptanceResult::Accepted);
+    assert_eq!(pda.decide("aaaa"), AcceptanceResult::Undecided);
+    assert!(!pda.accepts("aaaa"));
+    assert_eq!(guess_a_count().max_stack_height(), DEFAULT_MAX_STACK_HEIGHT);
+}
+
+#[test]
+fn branching_eps_pushes_hit_the_configuration_limit() {
+    // every stack over {A, B} can be pushed, but nothing is ever accepted:
+    // that is 2^64 configurations, the search gives up at the limit
+    let mut pda = PushdownAutomaton::builder()
+        .add_state("q").add_state("f")
+        .add_input_symbol("a")
+        .add_stack_symbol("Z").add_stack_symbol("A").add_stack_symbol("B").add_stack_symbol("C")
+        .start_state("q").stack_start_symbol("Z").add_terminal_state("f")
+        .add_transition("q", "eps", "Z", "A Z", "q")
+        .add_transition("q", "eps", "Z", "B Z", "q")
+        .add_transition("q", "eps", "A", "A A", "q")
+        .add_transition("q", "eps", "A", "B A", "q")
+        .add_transition("q", "eps", "B", "A B", "q")
+        .add_transition("q", "eps", "B", "B B", "q")
+        .add_transition("q", "a", "C", "C", "f")
+        .max_configurations(1000)
+        .build()
+        .unwrap();
+
+    assert_eq!(pda.max_configurations(), 1000);
+    assert_eq!(pda.decide("a"), AcceptanceResult::Undecided);
+    assert_eq!(pda.decide("b"), AcceptanceResult::Rejected);
+    assert!(pda.accepted_words(3, 10).is_empty());
+
+    pda.set_max_configurations(DEFAULT_MAX_CONFIGURATIONS);
+    assert_eq!(pda.decide("a"), AcceptanceResult::Undecided);
+}
+
+#[test]
+fn configurations_with_the_same_top_are_told_apart() {
+    // X Z or Y Z is pushed, then A's on top of it, a is read on X and b on Y:
+    // the configurations with the A's on top only differ below them
+    let pda = PushdownAutomaton::builder()
+        .add_state("q").add_state("p").add_state("r").add_state("f")
+        .add_input_symbol("a").add_input_symbol("b")
+        .add_stack_symbol("Z").add_stack_symbol("X").add_stack_symbol("Y").add_stack_symbol("A")
+        .start_state("q").stack_start_symbol("Z").add_terminal_state("f")
+        .add_transition("q", "eps", "Z", "X Z", "p")
+        .add_transition("q", "eps", "Z", "Y Z", "p")
+        .add_transition("p", "eps", "X", "A A A A X", "r")
+        .add_transition("p", "eps", "Y", "A A A A Y", "r")
+        .add_transition("r", "eps", "A", "eps", "r")
+        .add_transition("r", "a", "X", "X", "f")
+        .add_transition("r", "b", "Y", "Y", "f")
+        .build()
+        .unwrap();
+
+    assert_eq!(pda.decide("a"), AcceptanceResult::Accepted);
+    assert_eq!(pda.decide("b"), AcceptanceResult::Accepted);
+    assert_eq!(pda.decide("ab"), AcceptanceResult::Rejected);
+    let run = pda.accepting_run("b").unwrap();
+    assert_eq!(run.transitions.len(), 7);
+    assert_eq!(run.configurations.last().unwrap().stack, symbols(&["Y", "Z"]));
+}
+
+fn symbols(list: &[&str]) -> Vec<String> {
+    list.iter().map(|symbol| symbol.to_string()).collect()
+}
+
+#[test]
+fn accepting_run_is_a_shortest_computation() {
+    let pda = load("../resources/fel02/form_I.B.2.txt");
+    let run = pda.accepting_run("ab").unwrap();
+
+    assert_eq!(run.configurations.len(), 5);
+    assert_eq!(run.transitions.len(), 4);
+    assert_eq!(run.configurations[0], PushdownConfiguration { state: "p".to_string(), remaining_input: symbols(&["a", "b"]), stack: symbols(&["0"]) });
+    assert_eq!(run.configurations[1].stack, symbols(&["0", "0"]));
+    assert_eq!(run.configurations[4], PushdownConfiguration { state: "r".to_string(), remaining_input: Vec::new(), stack: Vec::new() });
+
+    // every step follows from the previous configuration by its transition
+    for (index, transition) in run.transitions.iter().enumerate() {
+        let (before, after) = (&run.configurations[index], &run.configurations[index + 1]);
+        assert_eq!(transition.current_state(), before.state);
+        assert_eq!(transition.stack_symbol(), before.stack[0]);
+        assert_eq!(transition.next_state(), after.state);
+    }
+}
+
+#[test]
+fn no_accepting_run_for_rejected_words() {
+    let pda = load("../resources/fel02/form_I.B.2.txt");
+    assert!(pda.accepting_run("abab").is_none());
+    assert!(pda.accepting_run("c").is_none());
+
+    let mut bounded = guess_a_count();
+    bounded.set_max_stack_height(2);
+    assert!(bounded.accepting_run("aaa").is_none());
+    assert_eq!(bounded.accepting_run("a").unwrap().configurations.last().unwrap().state, "f");
+}
+
+#[test]
+fn run_is_printed_as_a_derivation_table() {
+    let pda = load("../resources/fel02/form_I.B.2.txt");
+    assert_eq!(
+        pda.accepting_run("ab").unwrap().to_string(),
+        "  (p, ab, 0)\n\
+         ⊢ (p, b, 00)       (p, a, 0) -> (p, 00)\n\
+         ⊢ (q, b, 00)       (p, eps, 0) -> (q, 0)\n\
+         ⊢ (q, eps, 0)      (q, b, 0) -> (q, eps)\n\
+         ⊢ (r, eps, eps)    (q, eps, 0) -> (r, eps)\n"
+    );
+
+    // multi character stack symbols are separated by spaces
+    let pda = load("../resources/fel02/form_I.B.2_b.txt");
+    let table = pda.accepting_run("abbcc").unwrap().to_string();
+    assert!(table.contains("(q2, bcc, z1)"), "{}", table);
+    assert!(table.contains("(q2, cc, z1 z1)"), "{}", table);
+    assert!(table.contains("(q2, b, z1) -> (q2, z1 z1)"), "{}", table);
+}
+
+// one state q, input a, stack symbols Z and ZZ
+fn minimal_builder() -> PushdownAutomatonBuilder {
+    PushdownAutomaton::builder()
+        .add_state("q")
+        .add_input_symbol("a")
+        .add_stack_symbol("Z")
+        .add_stack_symbol("ZZ")
+        .start_state("q")
+        .stack_start_symbol("Z")
+}
+
+#[test]
+fn builder_reports_the_first_problem() {
+    let no_start = PushdownAutomaton::builder().add_state("q").add_stack_symbol("Z").stack_start_symbol("Z");
+    assert_eq!(no_start.build().unwrap_err(), PushdownBuildError::MissingStartState);
+    let no_bottom = PushdownAutomaton::builder().add_state("q").start_state("q");
+    assert_eq!(no_bottom.build().unwrap_err(), PushdownBuildError::MissingStackStartSymbol);
+
+    let cases = [
+        (minimal_builder().start_state("p"), PushdownBuildError::UnknownState("p".to_string())),
+        (minimal_builder().add_terminal_state("f"), PushdownBuildError::UnknownState("f".to_string())),
+        (minimal_builder().add_transition("q", "a", "Z", "Z", "p"), PushdownBuildError::UnknownState("p".to_string())),
+        (minimal_builder().add_transition("q", "b", "Z", "Z", "q"), PushdownBuildError::UnknownInputSymbol("b".to_string())),
+        (minimal_builder().stack_start_symbol("Y"), PushdownBuildError::UnknownStackSymbol("Y".to_string())),
+        (minimal_builder().add_transition("q", "a", "Y", "Z", "q"), PushdownBuildError::UnknownStackSymbol("Y".to_string())),
+        (minimal_builder().add_transition("q", "a", "Z", "Z Y", "q"), PushdownBuildError::UnknownStackSymbol("Y".to_string())),
+        (minimal_builder().add_input_symbol("eps"), PushdownBuildError::ReservedSymbol("eps".to_string())),
+        (minimal_builder().add_stack_symbol("eps"), PushdownBuildError::ReservedSymbol("eps".to_string())),
+    ];
+    for (builder, error) in cases {
+        assert_eq!(builder.build().unwrap_err(), error);
+    }
+}
+
+#[test]
+fn builder_splits_pushes_greedily() {
+    let pda = minimal_builder()
+        .add_transition("q", "a", "Z", "ZZZ", "q")
+        .add_transition("q", "a", "ZZ", "Z ZZ", "q")
+        .add_transition("q", "eps", "Z", "eps", "q")
+        .build()
+        .unwrap();
+
+    let pushes: Vec<&[String]> = pda.transitions().iter().map(|transition| transition.new_stack_symbols()).collect();
+    assert_eq!(pushes, [&symbols(&["ZZ", "Z"])[..], &symbols(&["Z", "ZZ"])[..], &[][..]]);
+    assert_eq!(pda.transitions()[0].to_string(), "(q, a, Z) -> (q, ZZ Z)");
+    assert_eq!(pda.transitions()[2].input_symbol(), "eps");
+}
+
+#[test]
+fn builder_keeps_settings() {
+    let pda = minimal_builder()
+        .add_terminal_state("q")
+        .acceptance_mode(AcceptanceMode::EmptyStack)
+        .max_stack_height(5)
+        .build()
+        .unwrap();
+    assert_eq!(pda.start_state(), "q");
+    assert_eq!(pda.stack_start_symbol(), "Z");
+    assert_eq!(pda.acceptance_mode(), AcceptanceMode::EmptyStack);
+    assert_eq!(pda.max_stack_height(), 5);
+    assert!(pda.terminal_states().contains("q"));
+    assert!(!pda.accepts(""));
+}
@@ -0,0 +1,139 @@
+use automata_lib::{Automaton, DeterministicAutomaton, Regex};
+use std::collections::HashSet;
+
+fn alphabet(symbols: &[&str]) -> HashSet<String> {
+    symbols.iter().map(|symbol| symbol.to_string()).collect()
+}
+
+fn symbol(name: &str) -> Regex {
+    Regex::Symbol(name.to_string())
+}
+
+fn union(left: Regex, right: Regex) -> Regex {
+    Regex::Union(Box::new(left), Box::new(right))
+}
+
+fn concat(left: Regex, right: Regex) -> Regex {
+    Regex::Concat(Box::new(left), Box::new(right))
+}
+
+#[test]
+fn star_binds_tighter_than_concatenation_and_union() {
+    let abc = alphabet(&["a", "b", "c"]);
+    let expected = union(concat(symbol("a"), symbol("b")), Regex::Star(Box::new(symbol("c"))));
+    assert_eq!(Regex::parse("ab|c*", &abc).unwrap(), expected);
+    assert_eq!(Regex::parse("a b | c *", &abc).unwrap(), expected);
+}
+
+#[test]
+fn parentheses_group() {
+    let abc = alphabet(&["a", "b", "c"]);
+    assert_eq!(Regex::parse("(a|b)c", &abc).unwrap(), concat(union(symbol("a"), symbol("b")), symbol("c")));
+    assert_eq!(Regex::parse("(ab)+", &abc).unwrap(), Regex::Plus(Box::new(concat(symbol("a"), symbol("b")))));
+}
+
+#[test]
+fn eps_and_empty_set() {
+    let ab = alphabet(&["a", "b"]);
+    assert_eq!(Regex::parse("a|eps", &ab).unwrap(), union(symbol("a"), Regex::Epsilon));
+    assert_eq!(Regex::parse("a|ε", &ab).unwrap(), union(symbol("a"), Regex::Epsilon));
+    assert_eq!(Regex::parse("∅", &ab).unwrap(), Regex::Empty);
+}
+
+#[test]
+fn alphabet_spelling_eps_reads_symbols() {
+    let eps = alphabet(&["e", "p", "s"]);
+    assert_eq!(Regex::parse("eps", &eps).unwrap(), concat(concat(symbol("e"), symbol("p")), symbol("s")));
+    assert_eq!(Regex::parse("ε", &eps).unwrap(), Regex::Epsilon);
+
+    // "e" alone can not spell eps, so the keyword still works
+    assert_eq!(Regex::parse("e|eps", &alphabet(&["e"])).unwrap(), union(symbol("e"), Regex::Epsilon));
+}
+
+#[test]
+fn multi_character_symbols_match_longest_first() {
+    let symbols = alphabet(&["a", "ab", "b"]);
+    assert_eq!(Regex::parse("ab", &symbols).unwrap(), symbol("ab"));
+    assert_eq!(Regex::parse("a b", &symbols).unwrap(), concat(symbol("a"), symbol("b")));
+}
+
+#[test]
+fn rejects_unknown_symbols() {
+    let ab = alphabet(&["a", "b"]);
+    assert!(Regex::parse("ac", &ab).is_err());
+    assert!(Regex::parse("a.b", &ab).is_err());
+}
+
+#[test]
+fn rejects_unbalanced_parentheses() {
+    let ab = alphabet(&["a", "b"]);
+    assert!(Regex::parse("(a|b", &ab).is_err());
+    assert!(Regex::parse("a|b)", &ab).is_err());
+    assert!(Regex::parse("()", &ab).is_err());
+    assert!(Regex::parse("a|", &ab).is_err());
+}
+
+#[test]
+fn thompson_construction_accepts_the_language() {
+    let ab = alphabet(&["a", "b"]);
+    let ndfa = Regex::parse("(a|b)*abb", &ab).unwrap().to_nondeterministic(&ab);
+    for word in ["abb", "aabb", "babb", "abababb"] {
+        assert!(ndfa.accepts(word), "{}", word);
+    }
+    for word in ["", "ab", "abba", "bbb"] {
+        assert!(!ndfa.accepts(word), "{}", word);
+    }
+
+    let ndfa = Regex::parse("a+b?", &ab).unwrap().to_nondeterministic(&ab);
+    assert!(ndfa.accepts("a") && ndfa.accepts("aab") && !ndfa.accepts("b") && !ndfa.accepts("abb"));
+
+    assert!(Regex::parse("eps", &ab).unwrap().to_nondeterministic(&ab).accepts(""));
+    let empty = Regex::parse("∅", &ab).unwrap().to_nondeterministic(&ab);
+    assert!(!empty.accepts("") && !empty.accepts("a"));
+}
+
+fn dfa_of(pattern: &str, symbols: &HashSet<String>) -> DeterministicAutomaton {
+    Regex::parse(pattern, symbols).unwrap().to_nondeterministic(symbols).to_deterministic()
+}
+
+fn check_round_trip(dfa: &DeterministicAutomaton) {
+    let text = dfa.to_regex().to_string_over(&dfa.alphabet);
+    let reparsed = dfa_of(&text, &dfa.alphabet);
+    assert!(reparsed == *dfa, "{} does not describe the same language", text);
+}
+
+#[test]
+fn to_regex_round_trips() {
+    let ab = alphabet(&["a", "b"]);
+    for pattern in ["(a|b)*abb", "a*b*", "(ab|ba)+", "eps", "∅", "a?b?a?", "(a(ab)*b)*"] {
+        check_round_trip(&dfa_of(pattern, &ab));
+    }
+
+    for file_name in ["../resources/fel03/form_I.B.3.txt", "../resources/fel03/form_I.B.3_b.txt"] {
+        let mut dfa = DeterministicAutomaton::new();
+        dfa.build_from_file(file_name).unwrap();
+        check_round_trip(&dfa);
+    }
+}
+
+#[test]
+fn to_regex_round_trips_with_multi_character_symbols() {
+    let symbols = alphabet(&["a", "b", "ab"]);
+    let dfa = dfa_of("a b | ab ab", &symbols);
+    check_round_trip(&dfa);
+
+    let text = concat(symbol("a"), symbol("b")).to_string_over(&symbols);
+    assert_eq!(text, "a b");
+    assert_eq!(Regex::parse(&text, &symbols).unwrap(), concat(symbol("a"), symbol("b")));
+}
+
+#[test]
+fn to_regex_round_trips_when_the_alphabet_spells_eps() {
+    let eps = alphabet(&["e", "p", "s"]);
+    let dfa = dfa_of("e p s | ε | s*", &eps);
+    check_round_trip(&dfa);
+
+    let word = concat(concat(symbol("e"), symbol("p")), symbol("s"));
+    assert_eq!(word.to_string(), "e p s");
+    assert_eq!(union(word, Regex::Epsilon).to_string_over(&eps), "e p s|ε");
+}
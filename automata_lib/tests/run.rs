@@ -0,0 +1,61 @@
+use automata_lib::{Automaton, DeterministicAutomaton, DeterministicRun};
+use std::collections::HashSet;
+
+fn states(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+fn fel03() -> DeterministicAutomaton {
+    let mut dfa = DeterministicAutomaton::new();
+    dfa.build_from_file("../resources/fel03/form_I.B.3.txt").unwrap();
+    dfa
+}
+
+#[test]
+fn run_lists_the_visited_states() {
+    let dfa = fel03();
+    assert_eq!(dfa.run("abab"), DeterministicRun { states: states(&["p", "q", "r", "p", "r"]), stuck_at: None, accepted: true });
+    assert_eq!(dfa.run("abba"), DeterministicRun { states: states(&["p", "q", "r", "s", "q"]), stuck_at: None, accepted: true });
+    assert_eq!(dfa.run(""), DeterministicRun { states: states(&["p"]), stuck_at: None, accepted: false });
+    assert_eq!(dfa.run("a b"), dfa.run("ab"));
+}
+
+#[test]
+fn accepts_agrees_with_run() {
+    let dfa = fel03();
+    for word in ["", "a", "b", "ab", "ba", "aab", "abab", "bbbb"] {
+        assert_eq!(dfa.accepts(word), dfa.run(word).accepted, "{}", word);
+    }
+}
+
+#[test]
+fn run_gets_stuck_on_missing_transitions() {
+    let mut dfa = fel03();
+    dfa.transitions.remove(&("r".to_string(), "a".to_string()));
+
+    let run = dfa.run("abab");
+    assert_eq!(run, DeterministicRun { states: states(&["p", "q", "r"]), stuck_at: Some(2), accepted: false });
+    assert!(!dfa.accepts("abab"));
+
+    // symbols outside the alphabet have no transition
+    assert_eq!(dfa.run("ac").stuck_at, Some(1));
+}
+
+#[test]
+fn run_symbols_takes_multi_character_symbols() {
+    let mut dfa = DeterministicAutomaton::new();
+    dfa.states = HashSet::from(["q".to_string(), "f".to_string()]);
+    dfa.alphabet = HashSet::from(["ab".to_string(), "c".to_string()]);
+    dfa.start_states.insert("q".to_string());
+    dfa.terminal_states.insert("f".to_string());
+    dfa.transitions.insert(("q".to_string(), "ab".to_string()), "f".to_string());
+    dfa.transitions.insert(("f".to_string(), "c".to_string()), "q".to_string());
+
+    assert!(dfa.run_symbols(&states(&["ab", "c", "ab"])).accepted);
+    assert_eq!(dfa.run_symbols(&states(&["ab", "ab"])).stuck_at, Some(1));
+    assert!(dfa.accepts("abcab"));
+
+    // without a start state the run is stuck before the first symbol
+    dfa.start_states.clear();
+    assert_eq!(dfa.run_symbols(&[]), DeterministicRun { states: Vec::new(), stuck_at: Some(0), accepted: false });
+}
@@ -0,0 +1,61 @@
+use automata_lib::{Automaton, NonDeterministicAutomaton, StateNaming};
+use std::collections::HashSet;
+
+fn names(states: &[&str]) -> HashSet<String> {
+    states.iter().map(|state| state.to_string()).collect()
+}
+
+// words ending in ab, with an eps move from the start state
+fn ends_with_ab() -> NonDeterministicAutomaton {
+    let mut nfa = NonDeterministicAutomaton::new();
+    nfa.states = names(&["0", "1", "2", "3"]);
+    nfa.alphabet = names(&["a", "b", "eps"]);
+    nfa.start_states = names(&["0"]);
+    nfa.terminal_states = names(&["3"]);
+    for (from, symbol, to) in [("0", "eps", "1"), ("1", "a", "1"), ("1", "b", "1"), ("1", "a", "2"), ("2", "b", "3")] {
+        nfa.add_transition(from.to_string(), symbol.to_string(), to.to_string());
+    }
+    nfa
+}
+
+#[test]
+fn index_naming_numbers_states_in_discovery_order() {
+    let dfa = ends_with_ab().to_deterministic_with(StateNaming::Index);
+    // {0,1}, then {1,2} on a and {1} on b, then {1,3}
+    assert_eq!(dfa.states, names(&["0", "1", "2", "3"]));
+    assert_eq!(dfa.start_states, names(&["0"]));
+    assert_eq!(dfa.terminal_states, names(&["3"]));
+    assert_eq!(dfa.transitions[&("0".to_string(), "a".to_string())], "1");
+    assert_eq!(dfa.transitions[&("0".to_string(), "b".to_string())], "2");
+    assert_eq!(dfa.transitions[&("1".to_string(), "b".to_string())], "3");
+    assert!(!dfa.alphabet.contains("eps"));
+}
+
+#[test]
+fn subset_naming_lists_the_nfa_states() {
+    let dfa = ends_with_ab().to_deterministic_with(StateNaming::Subset);
+    assert_eq!(dfa.states, names(&["{0,1}", "{1,2}", "{1}", "{1,3}"]));
+    assert_eq!(dfa.start_states, names(&["{0,1}"]));
+    assert_eq!(dfa.terminal_states, names(&["{1,3}"]));
+    assert_eq!(dfa.transitions[&("{0,1}".to_string(), "b".to_string())], "{1}");
+    assert_eq!(dfa.transitions[&("{1,2}".to_string(), "b".to_string())], "{1,3}");
+}
+
+#[test]
+fn namings_accept_the_same_words() {
+    let nfa = ends_with_ab();
+    let by_index = nfa.to_deterministic_with(StateNaming::Index);
+    let by_subset = nfa.to_deterministic_with(StateNaming::Subset);
+    assert!(by_index == by_subset);
+    assert!(by_index == nfa.to_deterministic());
+    for word in ["ab", "aab", "bab", "abab"] {
+        assert!(by_subset.accepts(word), "{}", word);
+    }
+    for word in ["", "a", "ba", "abb"] {
+        assert!(!by_subset.accepts(word), "{}", word);
+    }
+
+    let mut nfa = NonDeterministicAutomaton::new();
+    nfa.build_from_file("../resources/fel04/form_I.B.4.txt").unwrap();
+    assert!(nfa.to_deterministic_with(StateNaming::Index) == nfa.to_deterministic_with(StateNaming::Subset));
+}
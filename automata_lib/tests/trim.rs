@@ -0,0 +1,70 @@
+use automata_lib::DeterministicAutomaton;
+use std::collections::HashSet;
+
+fn names(states: &[&str]) -> HashSet<String> {
+    states.iter().map(|state| state.to_string()).collect()
+}
+
+fn sorted(states: &[&str]) -> Vec<String> {
+    states.iter().map(|state| state.to_string()).collect()
+}
+
+// p -a-> q (terminal) -b-> d (dead, loops), u (unreachable) -a-> q
+fn with_useless_states() -> DeterministicAutomaton {
+    let mut dfa = DeterministicAutomaton::new();
+    dfa.states = names(&["p", "q", "d", "u"]);
+    dfa.alphabet = names(&["a", "b"]);
+    dfa.start_states = names(&["p"]);
+    dfa.terminal_states = names(&["q"]);
+    for (from, symbol, to) in [("p", "a", "q"), ("q", "b", "d"), ("d", "a", "d"), ("d", "b", "d"), ("u", "a", "q")] {
+        dfa.transitions.insert((from.to_string(), symbol.to_string()), to.to_string());
+    }
+    dfa
+}
+
+#[test]
+fn trim_returns_the_removed_states_sorted() {
+    let mut dfa = with_useless_states();
+    assert_eq!(dfa.trim(), sorted(&["d", "u"]));
+    assert_eq!(dfa.states, names(&["p", "q"]));
+    assert_eq!(dfa.transitions.len(), 1);
+    assert!(dfa.accepts("a") && !dfa.accepts("ab"));
+
+    // trimming twice removes nothing
+    assert!(dfa.trim().is_empty());
+}
+
+#[test]
+fn remove_unreachable_keeps_dead_states() {
+    let mut dfa = with_useless_states();
+    assert_eq!(dfa.remove_unreachable_states(), sorted(&["u"]));
+    assert_eq!(dfa.states, names(&["p", "q", "d"]));
+    assert!(!dfa.transitions.contains_key(&("u".to_string(), "a".to_string())));
+}
+
+#[test]
+fn trim_of_the_empty_language_removes_everything() {
+    let mut dfa = with_useless_states();
+    dfa.terminal_states.clear();
+    assert_eq!(dfa.trim(), sorted(&["d", "p", "q", "u"]));
+    assert!(dfa.states.is_empty() && dfa.start_states.is_empty() && dfa.transitions.is_empty());
+}
+
+#[test]
+fn trim_of_a_long_chain() {
+    // 0 -a-> 1 -a-> ... -a-> n, terminal n, every state also has a b edge to a dead state
+    let n = 20_000;
+    let mut dfa = DeterministicAutomaton::new();
+    dfa.alphabet = names(&["a", "b"]);
+    dfa.start_states = names(&["0"]);
+    dfa.terminal_states = HashSet::from([n.to_string()]);
+    for state in 0..n {
+        dfa.transitions.insert((state.to_string(), "a".to_string()), (state + 1).to_string());
+        dfa.transitions.insert((state.to_string(), "b".to_string()), format!("dead{}", state));
+    }
+    dfa.states = dfa.transitions.iter().flat_map(|((from, _), to)| [from.clone(), to.clone()]).collect();
+
+    assert_eq!(dfa.trim().len(), n);
+    assert_eq!(dfa.states.len(), n + 1);
+    assert_eq!(dfa.transitions.len(), n);
+}
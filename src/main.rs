@@ -1,15 +1,26 @@
-use automata_lib::{self, NonDeterministicAutomaton, PushdownAutomaton, Automaton, DeterministicAutomaton};
+use automata_lib::{self, ContextFreeGrammar, NonDeterministicAutomaton, PushdownAutomaton, Automaton, DeterministicAutomaton, AcceptingSide, MinimizationReport, ShortlexWords, format_word};
 use std::fs::File;
 use std::io::{self, BufRead, BufReader};
 use std::path::Path;
 
 fn read_file(file_name: &str) -> io::Result<Vec<String>> {
     let path = Path::new(file_name);
-    let file = File::open(&path)?;
+    let file = File::open(path)?;
     let lines = BufReader::new(file).lines().collect::<Result<_, _>>().expect("couldnt read file");
     Ok(lines)
 }
 
+const FIRST_WORDS: usize = 20;
+
+fn print_first_words(words: Vec<String>) {
+    let words: Vec<&str> = words.iter().map(|word| if word.is_empty() { "eps" } else { word.as_str() }).collect();
+    println!("first {} accepted words: {}", words.len(), words.join(", "));
+}
+
+fn first_words(words: ShortlexWords) -> Vec<String> {
+    words.take(FIRST_WORDS).map(|word| format_word(&word)).collect()
+}
+
 fn fel_1_a03() -> io::Result<()>{
     println!("fel I_a_03");
     let mut ndfa = NonDeterministicAutomaton::new();
@@ -17,6 +28,7 @@ fn fel_1_a03() -> io::Result<()>{
     ndfa.write_dot_code("output/1.A.3/ndfa1.dot")?;
     ndfa.remove_unreachable_states();
     ndfa.write_dot_code("output/1.A.3/ndfa2.dot")?;
+    print_first_words(first_words(ndfa.accepted_words(None)));
     
 
     Ok(())
@@ -37,6 +49,19 @@ fn fel_1_a04() -> io::Result<()>{
     Ok(())
 }
 
+fn print_equivalence(dfa1: &DeterministicAutomaton, dfa2: &DeterministicAutomaton) {
+    match dfa1.equivalence_witness(dfa2) {
+        None => println!("equals"),
+        Some(witness) => {
+            let accepted_by = match witness.accepted_by {
+                AcceptingSide::Left => "dfa1",
+                AcceptingSide::Right => "dfa2",
+            };
+            println!("not equals, {} is only accepted by {}", format_word(&witness.word), accepted_by);
+        }
+    }
+}
+
 fn fel_01() -> io::Result<()>{
     println!("fel 01");
     let mut dfa1 = DeterministicAutomaton::new();
@@ -46,33 +71,21 @@ fn fel_01() -> io::Result<()>{
     dfa2.build_from_file("resources/fel01/form_I.B.1_a2.txt")?;
     dfa1.write_dot_code("output/fel01/dfa1_a.dot")?;
     dfa2.write_dot_code("output/fel01/dfa2_a.dot")?;
-    if dfa1 == dfa2 {
-        println!("equals");
-    } else {
-        println!("not equals");
-    }
+    print_equivalence(&dfa1, &dfa2);
 
     println!("B");
     dfa1.build_from_file("resources/fel01/form_I.B.1_b1.txt")?;
     dfa2.build_from_file("resources/fel01/form_I.B.1_b2.txt")?;
     dfa1.write_dot_code("output/fel01/dfa1_b.dot")?;
     dfa2.write_dot_code("output/fel01/dfa2_b.dot")?;
-    if dfa1 == dfa2 {
-        println!("equals");
-    } else {
-        println!("not equals");
-    }
+    print_equivalence(&dfa1, &dfa2);
 
     println!("C");
     dfa1.build_from_file("resources/fel01/form_I.B.1_c1.txt")?;
     dfa2.build_from_file("resources/fel01/form_I.B.1_c2.txt")?;
     dfa1.write_dot_code("output/fel01/dfa1_c.dot")?;
     dfa2.write_dot_code("output/fel01/dfa2_c.dot")?;
-    if dfa1 == dfa2 {
-        println!("equals");
-    } else {
-        println!("not equals");
-    }
+    print_equivalence(&dfa1, &dfa2);
 
     Ok(())
 }
@@ -83,16 +96,34 @@ fn fel_02() -> io::Result<()>{
     let mut pda = PushdownAutomaton::new();
     pda.build_from_file("resources/fel02/form_I.B.2.txt")?;
     pda.write_dot_code("output/fel02/pda.dot")?;
+    print_first_words(pda.accepted_words(8, FIRST_WORDS));
     pda.check_for_file("resources/fel02/form_I.B.2_szavak.txt")?;
 
     println!("B");
     pda.build_from_file("resources/fel02/form_I.B.2_b.txt")?;
     pda.write_dot_code("output/fel02/pda_b.dot")?;
+    print_first_words(pda.accepted_words(8, FIRST_WORDS));
     pda.check_for_file("resources/fel02/form_I.B.2_b_szavak.txt")?;
 
     Ok(())
 }
 
+fn print_minimization_report(report: &MinimizationReport) {
+    if report.is_minimal() {
+        println!("its minimalized");
+        return;
+    }
+    if !report.removed_unreachable.is_empty() {
+        println!("unreachable states removed: {}", report.removed_unreachable.join(", "));
+    }
+    if !report.removed_dead.is_empty() {
+        println!("dead states removed: {}", report.removed_dead.join(", "));
+    }
+    for block in &report.merged_blocks {
+        println!("merged {} into {}", block.states.join(", "), block.representative);
+    }
+}
+
 fn fel_03() -> io::Result<()>{
     println!("fel 03");
     let mut dfa = DeterministicAutomaton::new();
@@ -100,25 +131,19 @@ fn fel_03() -> io::Result<()>{
 
     dfa.build_from_file("resources/fel03/form_I.B.3.txt")?;
     dfa.write_dot_code("output/fel03/dfa_a.dot")?;
-    let (res, minimized_dfa) = dfa.is_minimized();
-    if res {
-        println!("its minimalized");
-        minimized_dfa.write_dot_code("output/fel03/dfa_minimized_a.dot")?;
-    } else {
-        minimized_dfa.write_dot_code("output/fel03/dfa_minimized_a.dot")?;
-    }
+    print_first_words(first_words(dfa.accepted_words(None)));
+    let report = dfa.is_minimized();
+    print_minimization_report(&report);
+    report.automaton.write_dot_code("output/fel03/dfa_minimized_a.dot")?;
 
     println!("B");
 
     dfa.build_from_file("resources/fel03/form_I.B.3_b.txt")?;
     dfa.write_dot_code("output/fel03/dfa_b.dot")?;
-    let (res, minimized_dfa) = dfa.is_minimized();
-    if res {
-        println!("its minimalized");
-        minimized_dfa.write_dot_code("output/fel03/dfa_minimized_b.dot")?;
-    } else {
-        minimized_dfa.write_dot_code("output/fel03/dfa_minimized_b.dot")?;
-    }
+    print_first_words(first_words(dfa.accepted_words(None)));
+    let report = dfa.is_minimized();
+    print_minimization_report(&report);
+    report.automaton.write_dot_code("output/fel03/dfa_minimized_b.dot")?;
 
     Ok(())
 }
@@ -132,6 +157,7 @@ fn fel_04() -> io::Result<()>{
     ndfa.write_dot_code("output/fel04/ndfa_a.dot")?;
     let mut dfa = ndfa.to_deterministic();
     dfa.write_dot_code("output/fel04/dfa_a.dot")?;
+    print_first_words(first_words(dfa.accepted_words(None)));
 
     println!("B");
 
@@ -139,6 +165,21 @@ fn fel_04() -> io::Result<()>{
     ndfa.write_dot_code("output/fel04/ndfa_b.dot")?;
     dfa = ndfa.to_deterministic();
     dfa.write_dot_code("output/fel04/dfa_b.dot")?;
+    print_first_words(first_words(dfa.accepted_words(None)));
+
+    Ok(())
+}
+
+fn grammar() -> io::Result<()> {
+    println!("grammar");
+    let mut cfg = ContextFreeGrammar::new();
+    cfg.build_from_file("resources/grammar/form_anbn.txt")?;
+    print!("{}", cfg);
+    if let Err(error) = cfg.validate() {
+        println!("invalid grammar: {}", error);
+    }
+    println!("unproductive nonterminals: {}", cfg.unproductive_nonterminals().join(", "));
+    println!("unreachable nonterminals: {}", cfg.unreachable_nonterminals().join(", "));
 
     Ok(())
 }
@@ -159,5 +200,8 @@ fn main() -> io::Result<()> {
     fel_03()?;
     println!();
 
-    fel_04()
+    fel_04()?;
+    println!();
+
+    grammar()
 }
\ No newline at end of file